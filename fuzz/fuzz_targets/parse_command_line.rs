@@ -0,0 +1,23 @@
+// Any line a client could send must parse without panicking, and whatever comes back has to
+// survive being written out and read again unchanged. The socket drops a connection on bad
+// UTF-8 before the parser sees it, so only valid strings are tried.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use stack_game::io::parse_command_line;
+use stack_game::CommandEvent;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(line) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Some(ev) = parse_command_line(line) else {
+        return;
+    };
+    let written = match &ev {
+        CommandEvent::Start { id, command } => format!("START {id} {command}"),
+        CommandEvent::Output { id, line } => format!("OUTPUT {id} {line}"),
+        CommandEvent::End { id, _exit_code } => format!("END {id} {_exit_code}"),
+    };
+    assert_eq!(parse_command_line(&written), Some(ev));
+});
@@ -0,0 +1,33 @@
+/* C API for the waitris game core; build with `cargo rustc --lib --release --features ffi
+ * --crate-type cdylib` (or staticlib). See src/ffi.rs for the details of each call. */
+#ifndef WAITRIS_H
+#define WAITRIS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct WaitrisGame WaitrisGame;
+
+/* mode: 0 classic, 1 zen, 2 sprint, 3 ultra. */
+WaitrisGame *waitris_game_new(uint64_t seed, uint32_t mode);
+void waitris_game_free(WaitrisGame *game);
+
+/* One protocol line: "START <id> <command>", "OUTPUT <id> <line>" or "END <id> <code>".
+   0 if applied, -1 if not. */
+int waitris_game_event(WaitrisGame *game, const char *line);
+
+/* action: 0 left, 1 right, 2 soft drop, 3 rotate, 4 hard drop, -1 none. */
+void waitris_game_step(WaitrisGame *game, int action, uint64_t elapsed_ms);
+
+/* Row-major from the top: 0 empty, 1 locked, 2 falling. Returns width * height and writes
+ * nothing if len is smaller. */
+size_t waitris_game_board(const WaitrisGame *game, uint8_t *buf, size_t len);
+size_t waitris_game_width(const WaitrisGame *game);
+size_t waitris_game_height(const WaitrisGame *game);
+
+uint64_t waitris_game_score(const WaitrisGame *game);
+uint64_t waitris_game_lines(const WaitrisGame *game);
+bool waitris_game_over(const WaitrisGame *game);
+
+#endif
@@ -1,9 +1,33 @@
 # stack-game shell hook
-# Sends START/END events to /tmp/stack-game.sock for each command.
+# Sends START/END events to the game's socket for each command, and in tmux the last line a
+# failed command printed as OUTPUT before its END (every command with STACK_RESULT=all).
 
-STACK_SOCK="/tmp/stack-game.sock"
+# Same lookup as the game: $STACK_SOCK, else $XDG_RUNTIME_DIR/waitris, else a per-user dir in
+# $TMPDIR or /tmp.
+if [ -z "$STACK_SOCK" ]; then
+  if [ -n "$XDG_RUNTIME_DIR" ]; then
+    STACK_SOCK="$XDG_RUNTIME_DIR/waitris/stack-game.sock"
+  else
+    STACK_SOCK="${TMPDIR:-/tmp}"
+    STACK_SOCK="${STACK_SOCK%/}/waitris-${USER:-user}/stack-game.sock"
+  fi
+fi
+STACK_SHARED_SOCK="$STACK_SOCK"
 STACK_CMD_ID=0
 
+# A game launched with --per-session puts its socket in the tmux session's environment, which
+# can change after this shell started, so it's looked up again for each command.
+stack_find_socket() {
+  STACK_SOCK="$STACK_SHARED_SOCK"
+  if [ -n "$TMUX" ]; then
+    local var
+    var=$(tmux show-environment STACK_SOCK 2>/dev/null)
+    case "$var" in
+      STACK_SOCK=?*) STACK_SOCK="${var#STACK_SOCK=}" ;;
+    esac
+  fi
+}
+
 stack_send() {
   local line="$1"
   if [ -S "$STACK_SOCK" ]; then
@@ -18,13 +42,24 @@ stack_send() {
 
 stack_preexec() {
   STACK_CMD_ID=$((STACK_CMD_ID + 1))
+  stack_find_socket
   STACK_LAST_CMD="$1"
   stack_send "START ${STACK_CMD_ID} ${STACK_LAST_CMD}"
 }
 
+# The prompt isn't drawn yet, so the pane's last non-blank line is the command's own.
+stack_last_line() {
+  tmux capture-pane -p -t "$TMUX_PANE" 2>/dev/null | awk 'NF { last = $0 } END { print last }'
+}
+
 stack_precmd() {
   local code=$?
   if [ -n "$STACK_CMD_ID" ]; then
+    if [ -n "$TMUX" ] && { [ "$code" -ne 0 ] || [ "$STACK_RESULT" = all ]; }; then
+      local out
+      out=$(stack_last_line)
+      [ -n "$out" ] && stack_send "OUTPUT ${STACK_CMD_ID} ${out}"
+    fi
     stack_send "END ${STACK_CMD_ID} ${code}"
   fi
 }
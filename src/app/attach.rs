@@ -0,0 +1,59 @@
+use std::io::{stdout, BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::paths::socket_path;
+use crate::Error;
+
+use super::events::AppEvent;
+use super::screen::{Screen, TuiGuard};
+
+// A UI for a `stack-game --daemon`: this terminal's events go up the socket, and the frames
+// the daemon draws for it come back down, already encoded for the terminal. The daemon has
+// no use for key releases, so the kitty protocol stays off. Ctrl-Z detaches.
+pub fn attach() -> Result<(), Error> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|e| Error::Socket(format!("no game listening on {}: {e}", path.display())))?;
+    let (width, height) = crossterm::terminal::size().map_err(Error::Terminal)?;
+    let hung_up = |e: std::io::Error| Error::Socket(format!("the game hung up: {e}"));
+    writeln!(stream, "ATTACH {width} {height}").map_err(hung_up)?;
+    let mut frames = BufReader::new(stream.try_clone().map_err(hung_up)?);
+    let mut reply = String::new();
+    frames.read_line(&mut reply).map_err(hung_up)?;
+    match reply.trim() {
+        "OK" => {}
+        "BUSY" => {
+            return Err(Error::Socket(
+                "that game has a screen of its own; run it with --daemon to attach".to_string(),
+            ));
+        }
+        _ => return Err(Error::Socket("the game hung up".to_string())),
+    }
+
+    let tui = TuiGuard::with_kitty(false)?;
+    let (tx, rx) = mpsc::channel();
+    tui.spawn_input(tx.clone());
+    thread::spawn(move || {
+        let mut out = stdout();
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = frames.read(&mut buf) {
+            if n == 0 || out.write_all(&buf[..n]).and_then(|_| out.flush()).is_err() {
+                break;
+            }
+        }
+        let _ = tx.send(AppEvent::Shutdown);
+    });
+    for ev in rx {
+        let AppEvent::Terminal(ev) = ev else {
+            break;
+        };
+        let json = serde_json::to_string(&ev).map_err(|e| Error::Socket(e.to_string()))?;
+        if writeln!(stream, "EVENT {json}").is_err() {
+            break;
+        }
+    }
+    drop(tui);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+use crate::game::GameEvent;
+use crate::Game;
+
+// STACK_TMUX_BOARD=1 keeps a one-line picture of the board in the tmux user option
+// @waitris_board (or the option it names, as in STACK_TMUX_BOARD=@board): the column heights
+// as bars, then the score. It's refreshed whenever the stack changes and unset when the game
+// goes, so a status line can show the game while its pane is zoomed away.
+pub(super) struct BoardArt {
+    option: TmuxOption,
+    // Feeds the one thread that talks to tmux, so updates land in order without holding up
+    // the game loop.
+    updates: Option<mpsc::Sender<String>>,
+    worker: Option<JoinHandle<()>>,
+    // What was last sent, so an unchanged board costs nothing.
+    shown: String,
+}
+
+#[derive(Clone)]
+struct TmuxOption {
+    name: String,
+    // The game's pane, standing for its session, when it's a --per-session game; else the
+    // option is global.
+    target: Option<String>,
+}
+
+impl BoardArt {
+    pub(super) fn from_env() -> Option<Self> {
+        std::env::var_os("TMUX")?;
+        let name = match std::env::var("STACK_TMUX_BOARD").unwrap_or_default().trim() {
+            "" | "0" => return None,
+            "1" => "@waitris_board".to_string(),
+            name if name.starts_with('@') => name.to_string(),
+            other => {
+                log::warn!("STACK_TMUX_BOARD: {other:?} isn't a user option (@name)");
+                return None;
+            }
+        };
+        let per_session = std::env::var_os("STACK_SOCK").is_some_and(|s| !s.is_empty());
+        let target = per_session.then(|| std::env::var("TMUX_PANE").ok()).flatten();
+        let option = TmuxOption { name, target };
+        let (updates, pending) = mpsc::channel::<String>();
+        let worker = {
+            let option = option.clone();
+            thread::spawn(move || {
+                while let Ok(mut art) = pending.recv() {
+                    // Only the newest picture is worth sending.
+                    art = pending.try_iter().last().unwrap_or(art);
+                    option.set(Some(&art));
+                }
+            })
+        };
+        Some(Self {
+            option,
+            updates: Some(updates),
+            worker: Some(worker),
+            shown: String::new(),
+        })
+    }
+
+    // Locks, clears, garbage and the end of the game move the stack or the score.
+    pub(super) fn changes_on(ev: &GameEvent) -> bool {
+        !matches!(ev, GameEvent::BombEarned { .. })
+    }
+
+    pub(super) fn publish(&mut self, game: &Game) {
+        let art = format!("[{}] {}", game.board.sparks(), game.score);
+        if art != self.shown
+            && let Some(updates) = &self.updates
+        {
+            let _ = updates.send(art.clone());
+            self.shown = art;
+        }
+    }
+}
+
+impl TmuxOption {
+    fn set(&self, value: Option<&str>) {
+        let mut cmd = Command::new("tmux");
+        cmd.arg("set-option");
+        match &self.target {
+            Some(pane) => cmd.args(["-q", "-t", pane]),
+            None => cmd.arg("-gq"),
+        };
+        match value {
+            Some(value) => cmd.args([&self.name, value]),
+            None => cmd.args(["-u", &self.name]),
+        };
+        let status = cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).status();
+        if let Err(e) = status {
+            log::warn!("tmux set-option {}: {e}", self.name);
+        }
+    }
+}
+
+// The worker finishes what it was sent before the option is unset, so nothing lands after.
+impl Drop for BoardArt {
+    fn drop(&mut self) {
+        self.updates = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        self.option.set(None);
+    }
+}
@@ -0,0 +1,124 @@
+use std::mem;
+
+use crate::ui::Thumbnail;
+use crate::{CommandEvent, Game};
+
+// STACK_BOARDS=multi: a command that starts while another is running gets a board of its own
+// instead of dealing into the same one. Only the board on screen plays; Tab brings up the
+// next. A side board goes away, its score added to the session totals, once its command has
+// ended and its last piece has landed.
+pub struct Boards {
+    multi: bool,
+    // Boards off screen, in Tab order, by the run each was opened for; `None` is the main game.
+    parked: Vec<(Option<u64>, Game)>,
+    // The run the board on screen was opened for; `None` while it's the main game.
+    shown: Option<u64>,
+}
+
+impl Boards {
+    // `multi` off routes everything to the main game, as if there were only the one board.
+    pub fn new(multi: bool) -> Self {
+        Self { multi, parked: Vec::new(), shown: None }
+    }
+
+    pub(super) fn from_env() -> Self {
+        Self::new(std::env::var("STACK_BOARDS").unwrap_or_default() == "multi")
+    }
+
+    // The board a command event belongs to, opening one for a START that needs it. A START
+    // that doesn't goes to the main game, even while a side board is on screen.
+    pub fn board_for<'a>(
+        &'a mut self,
+        game: &'a mut Game,
+        ev: &CommandEvent,
+    ) -> &'a mut Game {
+        let id = match ev {
+            CommandEvent::Start { id, .. } => {
+                let busy = game.active_run_count() > 0
+                    || self.parked.iter().any(|(_, board)| board.active_run_count() > 0);
+                if !self.multi || !busy {
+                    return self.main_mut(game);
+                }
+                let mut board = Game::with_seed(game.seed.wrapping_add(*id));
+                board.set_feed(game.feed());
+                board.start(game.mode, game.profile);
+                log::debug!("run {id} gets a board of its own");
+                self.parked.push((Some(*id), board));
+                return &mut self.parked.last_mut().expect("just pushed").1;
+            }
+            CommandEvent::Output { id, .. } | CommandEvent::End { id, .. } => *id,
+        };
+        match self.parked.iter_mut().find(|(_, board)| board.command_for_run(id).is_some()) {
+            Some((_, board)) => board,
+            None => game,
+        }
+    }
+
+    // Tab: park the board on screen at the back and bring up the one at the front.
+    pub fn switch(&mut self, game: &mut Game) {
+        if self.parked.is_empty() {
+            return;
+        }
+        let (run, mut next) = self.parked.remove(0);
+        mem::swap(game, &mut next);
+        self.parked.push((mem::replace(&mut self.shown, run), next));
+        game.reset_timers();
+    }
+
+    // Put the main game back on screen, e.g. for its game-over screen or to save it.
+    pub fn show_main(&mut self, game: &mut Game) {
+        if let Some(i) = self.parked.iter().position(|(run, _)| run.is_none()) {
+            let (_, mut main) = self.parked.remove(i);
+            mem::swap(game, &mut main);
+            self.parked.insert(i, (self.shown.take(), main));
+            game.reset_timers();
+        }
+    }
+
+    // The main game, wherever it is.
+    pub fn main<'a>(&'a self, game: &'a Game) -> &'a Game {
+        match self.parked.iter().find(|(run, _)| run.is_none()) {
+            Some((_, main)) => main,
+            None => game,
+        }
+    }
+
+    fn main_mut<'a>(&'a mut self, game: &'a mut Game) -> &'a mut Game {
+        match self.parked.iter_mut().find(|(run, _)| run.is_none()) {
+            Some((_, main)) => main,
+            None => game,
+        }
+    }
+
+    // Let go of side boards that are done, the one on screen included, handing them back for
+    // the session totals.
+    pub fn tidy(&mut self, game: &mut Game) -> Vec<Game> {
+        if self.shown.is_some() && done(game) {
+            self.show_main(game);
+        }
+        let (retired, parked) = mem::take(&mut self.parked)
+            .into_iter()
+            .partition(|(run, board)| run.is_some() && done(board));
+        self.parked = parked;
+        retired.into_iter().map(|(_, board)| board).collect()
+    }
+
+    // The boards off screen, as the sidebar lists them.
+    pub(super) fn thumbnails(&self) -> Vec<Thumbnail> {
+        self.parked
+            .iter()
+            .map(|(run, board)| Thumbnail {
+                sparks: board.board.sparks(),
+                label: run
+                    .and_then(|id| board.command_for_run(id))
+                    .unwrap_or("main")
+                    .to_string(),
+                score: board.score,
+            })
+            .collect()
+    }
+}
+
+fn done(board: &Game) -> bool {
+    board.game_over || !board.is_running()
+}
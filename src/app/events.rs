@@ -0,0 +1,94 @@
+use std::sync::mpsc;
+use std::thread;
+
+use crossterm::event::{self, Event};
+use signal_hook::consts::{SIGHUP, SIGTERM, SIGTSTP};
+use signal_hook::iterator::Signals;
+
+use crate::io::{Attach, Detach, Query, Quit, Spectator};
+use crate::CommandEvent;
+
+// Everything the app loop waits on arrives through one channel, so a single blocking
+// receive replaces polling each source in turn.
+pub(super) enum AppEvent {
+    Terminal(Event),
+    Command(CommandEvent),
+    Query(Query),
+    // SIGTERM/SIGHUP (tmux kill-pane, closed session): leave the loop so cleanup still runs.
+    Shutdown,
+    // QUIT on the socket, from `waitris stop`: the same, but on purpose.
+    Quit,
+    // SIGTSTP from outside (`kill -TSTP`); Ctrl-Z itself arrives as a key in raw mode.
+    Suspend,
+    // A UI coming and going over the socket, for the daemon.
+    Attach(Attach),
+    Detach(u64),
+    // A read-only viewer on the watch socket.
+    Spectator(Spectator),
+}
+
+impl From<CommandEvent> for AppEvent {
+    fn from(ev: CommandEvent) -> Self {
+        AppEvent::Command(ev)
+    }
+}
+
+impl From<Query> for AppEvent {
+    fn from(query: Query) -> Self {
+        AppEvent::Query(query)
+    }
+}
+
+impl From<Quit> for AppEvent {
+    fn from(_: Quit) -> Self {
+        AppEvent::Quit
+    }
+}
+
+impl From<Attach> for AppEvent {
+    fn from(client: Attach) -> Self {
+        AppEvent::Attach(client)
+    }
+}
+
+impl From<Detach> for AppEvent {
+    fn from(detach: Detach) -> Self {
+        AppEvent::Detach(detach.id)
+    }
+}
+
+impl From<Spectator> for AppEvent {
+    fn from(spectator: Spectator) -> Self {
+        AppEvent::Spectator(spectator)
+    }
+}
+
+// An attached UI's keys, mouse and resizes, as if they came from our own terminal.
+impl From<Event> for AppEvent {
+    fn from(ev: Event) -> Self {
+        AppEvent::Terminal(ev)
+    }
+}
+
+pub(super) fn spawn_terminal_reader(tx: mpsc::Sender<AppEvent>) {
+    thread::spawn(move || {
+        while let Ok(ev) = event::read() {
+            if tx.send(AppEvent::Terminal(ev)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+pub(super) fn spawn_signal_watcher(tx: mpsc::Sender<AppEvent>) -> std::io::Result<()> {
+    let mut signals = Signals::new([SIGTERM, SIGHUP, SIGTSTP])?;
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            let ev = if signal == SIGTSTP { AppEvent::Suspend } else { AppEvent::Shutdown };
+            if tx.send(ev).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+
+use crate::game::Action;
+
+// With real release events we run our own auto-repeat: a side move fires once, waits
+// DAS, then repeats every ARR; soft drop repeats every SOFT_DROP while held.
+const DAS: Duration = Duration::from_millis(170);
+const ARR: Duration = Duration::from_millis(50);
+const SOFT_DROP: Duration = Duration::from_millis(40);
+
+// Some terminals (Windows, kitty protocol) report Release and Repeat alongside Press, and a few
+// resend Press while a key is held. Track what is down so each physical press acts once.
+#[derive(Default)]
+pub(super) struct KeyTracker {
+    // Held keys and when each next auto-repeats.
+    held: HashMap<KeyCode, Instant>,
+    reports_release: bool,
+}
+
+impl KeyTracker {
+    // `reports_release` is known up front when the kitty protocol was negotiated.
+    pub(super) fn new(reports_release: bool) -> Self {
+        Self {
+            held: HashMap::new(),
+            reports_release,
+        }
+    }
+
+    // Whether this key event should reach the game.
+    pub(super) fn accept(&mut self, key: KeyEvent) -> bool {
+        match key.kind {
+            KeyEventKind::Press => {
+                let fresh = !self.held.contains_key(&key.code);
+                if fresh {
+                    let delay = if key.code == KeyCode::Down { SOFT_DROP } else { DAS };
+                    self.held.insert(key.code, Instant::now() + delay);
+                }
+                // Without releases we can't tell a held key from a new press, so trust every Press.
+                fresh || !self.reports_release
+            }
+            // Once releases arrive we drive repeats ourselves and ignore the terminal's.
+            KeyEventKind::Repeat => !self.reports_release && repeats(key.code),
+            KeyEventKind::Release => {
+                self.reports_release = true;
+                self.held.remove(&key.code);
+                false
+            }
+        }
+    }
+
+    // Held movement keys whose auto-repeat is due.
+    pub(super) fn due_repeats(&mut self) -> Vec<KeyCode> {
+        if !self.reports_release {
+            return Vec::new();
+        }
+        let now = Instant::now();
+        let mut due = Vec::new();
+        for (&code, next) in self.held.iter_mut() {
+            if repeats(code) && *next <= now {
+                *next = now + if code == KeyCode::Down { SOFT_DROP } else { ARR };
+                due.push(code);
+            }
+        }
+        due
+    }
+
+    pub(super) fn until_next_repeat(&self) -> Option<Duration> {
+        if !self.reports_release {
+            return None;
+        }
+        let now = Instant::now();
+        self.held
+            .iter()
+            .filter(|(code, _)| repeats(**code))
+            .map(|(_, next)| next.saturating_duration_since(now))
+            .min()
+    }
+
+    // Releases that happen while unfocused never reach us.
+    pub(super) fn release_all(&mut self) {
+        self.held.clear();
+    }
+}
+
+// The keymap: the game only ever sees the action a key stands for.
+pub(super) fn action_for(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Left => Some(Action::Left),
+        KeyCode::Right => Some(Action::Right),
+        KeyCode::Down => Some(Action::SoftDrop),
+        KeyCode::Up => Some(Action::Rotate),
+        KeyCode::Char(' ') => Some(Action::HardDrop),
+        _ => None,
+    }
+}
+
+// Only movement auto-repeats; rotate, slam and menu keys act once per press.
+fn repeats(code: KeyCode) -> bool {
+    matches!(code, KeyCode::Left | KeyCode::Right | KeyCode::Down)
+}
@@ -1,3 +1,22 @@
+mod attach;
+mod board_art;
+mod boards;
+mod events;
+mod keys;
+mod mouse;
+mod notify;
+mod playback;
 mod runtime;
+mod screen;
+mod simulate;
+mod sound;
+mod watch;
 
-pub use runtime::run;
+pub use attach::attach;
+pub use boards::Boards;
+pub use playback::playback;
+pub use runtime::{daemon, run};
+pub use simulate::simulate;
+pub use watch::watch;
+
+pub(crate) use screen::render_frame;
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+use crate::game::Action;
+use crate::ui::WellArea;
+use crate::Game;
+
+const DOUBLE_CLICK: Duration = Duration::from_millis(350);
+
+// Click a column to steer the piece there, double-click to slam, scroll to rotate.
+#[derive(Default)]
+pub(super) struct MouseControl {
+    last_click: Option<(Instant, usize)>,
+}
+
+impl MouseControl {
+    pub(super) fn handle(&mut self, ev: MouseEvent, game: &mut Game, well: &WellArea) {
+        match ev.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(column) = well.column_at(ev.column, ev.row) else {
+                    return;
+                };
+                let now = Instant::now();
+                let double = self
+                    .last_click
+                    .is_some_and(|(at, col)| col == column && now - at <= DOUBLE_CLICK);
+                if double {
+                    self.last_click = None;
+                    game.input(Action::HardDrop);
+                } else {
+                    self.last_click = Some((now, column));
+                    move_toward_column(game, column);
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                game.input(Action::Rotate);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn move_toward_column(game: &mut Game, column: usize) {
+    if !game.active_piece {
+        return;
+    }
+    let target = column as i32;
+    loop {
+        let cells = game.current.cells();
+        let min_x = cells.iter().map(|(x, _, _)| *x).min().unwrap_or(0);
+        let max_x = cells.iter().map(|(x, _, _)| *x).max().unwrap_or(0);
+        let center = (min_x + max_x) / 2;
+        let input = match (target - center).signum() {
+            -1 => Action::Left,
+            1 => Action::Right,
+            _ => break,
+        };
+        let before = game.current.x;
+        game.input(input);
+        if game.current.x == before {
+            break;
+        }
+    }
+}
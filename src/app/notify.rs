@@ -0,0 +1,137 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::Command;
+
+use crate::game::{Exit, Game};
+use crate::io::{desktop_notify, Osc};
+use crate::ui::format_duration;
+
+use super::screen::Screen;
+
+// Which finished commands pop a desktop notification: STACK_NOTIFY=fail for failures only,
+// =all for every one. Off unless set.
+#[derive(Clone, Copy, PartialEq)]
+enum NotifyOn {
+    Never,
+    Failures,
+    All,
+}
+
+impl NotifyOn {
+    fn from_env() -> Self {
+        match std::env::var("STACK_NOTIFY").unwrap_or_default().as_str() {
+            "all" | "1" => NotifyOn::All,
+            "fail" => NotifyOn::Failures,
+            _ => NotifyOn::Never,
+        }
+    }
+}
+
+// STACK_OSC_NOTIFY=9 or =777 has the terminal itself announce commands that finish while the
+// game is out of sight. Off unless set, since not every terminal knows either escape.
+fn osc_from_env() -> Option<Osc> {
+    match std::env::var("STACK_OSC_NOTIFY").unwrap_or_default().as_str() {
+        "9" => Some(Osc::Nine),
+        "777" => Some(Osc::SevenSevenSeven),
+        _ => None,
+    }
+}
+
+// What a finished command's notification says.
+struct Ending {
+    summary: &'static str,
+    command: String,
+    detail: String,
+}
+
+impl Ending {
+    fn of(game: &Game, id: u64, exit_code: i32) -> Option<Self> {
+        let command = game.command_for_run(id)?.to_string();
+        let took = format_duration(game.run_elapsed(id)?);
+        let (summary, detail) = match Exit::from_code(exit_code) {
+            Exit::Success => ("Command finished", format!("done in {took}")),
+            Exit::Failed => ("Command failed", format!("exit {exit_code} after {took}")),
+            Exit::Interrupted => ("Command interrupted", format!("after {took}")),
+            Exit::Crashed => ("Command crashed", format!("exit {exit_code} after {took}")),
+        };
+        Some(Ending { summary, command, detail })
+    }
+}
+
+// Called once a command's END is applied, while its run is still on the books.
+pub(super) fn notify_end(game: &Game, id: u64, exit_code: i32) {
+    // A command the player stopped themselves is no news, even under =all.
+    let exit = Exit::from_code(exit_code);
+    let wanted = match NotifyOn::from_env() {
+        NotifyOn::Never => false,
+        NotifyOn::Failures => matches!(exit, Exit::Failed | Exit::Crashed),
+        NotifyOn::All => exit != Exit::Interrupted,
+    };
+    if !wanted {
+        return;
+    }
+    let Some(ending) = Ending::of(game, id, exit_code) else {
+        return;
+    };
+    let body = format!("{}\n{}", ending.command, ending.detail);
+    if let Err(e) = desktop_notify(ending.summary, &body, exit != Exit::Success) {
+        log::warn!("desktop notification: {e}");
+    }
+}
+
+// The terminal-escape notification for a finished command, sent only when the player can't
+// see the game: its pane is unfocused, or in tmux its window isn't the one on screen. Inside
+// tmux the escape goes straight to the client's tty, since tmux keeps unknown escapes from a
+// pane to itself.
+pub(super) fn announce_end(
+    screen: &mut impl Screen,
+    game: &Game,
+    focused: bool,
+    id: u64,
+    exit_code: i32,
+) {
+    let Some(osc) = osc_from_env() else {
+        return;
+    };
+    let view = tmux_view();
+    if focused && view.as_ref().is_none_or(|view| view.on_screen) {
+        return;
+    }
+    let Some(ending) = Ending::of(game, id, exit_code) else {
+        return;
+    };
+    let escape = osc.notification(ending.summary, &format!("{} ({})", ending.command, ending.detail));
+    match view {
+        Some(view) => {
+            let sent = OpenOptions::new()
+                .write(true)
+                .open(&view.client_tty)
+                .and_then(|mut tty| tty.write_all(escape.as_bytes()));
+            if let Err(e) = sent {
+                log::warn!("notifying {}: {e}", view.client_tty);
+            }
+        }
+        None => screen.write_escape(&escape),
+    }
+}
+
+// Where the game's tmux pane stands with the client showing its session.
+struct TmuxView {
+    on_screen: bool,
+    client_tty: String,
+}
+
+fn tmux_view() -> Option<TmuxView> {
+    let pane = std::env::var("TMUX_PANE").ok()?;
+    let out = Command::new("tmux")
+        .args(["display-message", "-p", "-t", &pane])
+        .arg("#{window_active} #{session_attached} #{client_tty}")
+        .output()
+        .ok()?;
+    let out = String::from_utf8_lossy(&out.stdout);
+    let mut fields = out.split_whitespace();
+    let active = fields.next()? == "1";
+    let attached = fields.next()? != "0";
+    let client_tty = fields.next()?.to_string();
+    Some(TmuxView { on_screen: active && attached, client_tty })
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+
+use crate::game::{Recording, Step};
+use crate::persist::{self, PersonalBests};
+use crate::ui::{Hud, Theme};
+use crate::{Error, Game};
+
+use super::events::AppEvent;
+use super::screen::{Screen, TuiGuard};
+
+const FRAME: Duration = Duration::from_millis(50);
+
+// Play a recorded game back at its original pace: space pauses, → / n steps while paused,
+// q quits.
+pub fn playback(path: &str) -> Result<(), Error> {
+    let recording = persist::load_replay(Path::new(path))
+        .map_err(|e| Error::Config(format!("{path}: not a replay ({e})")))?;
+    let mut tui = TuiGuard::new()?;
+    play(&mut tui, &recording)
+}
+
+fn play(screen: &mut impl Screen, recording: &Recording) -> Result<(), Error> {
+    let (tx, rx) = mpsc::channel();
+    screen.spawn_input(tx);
+    let mut game = Game::with_seed(recording.seed);
+    let mut hud = Hud::new(PersonalBests::default(), Theme::from_env());
+    let steps = &recording.steps;
+    let mut next = 0;
+    let mut paused = false;
+    // Position in recording time; only moves while playing.
+    let mut position = Duration::ZERO;
+    let mut last_update = Instant::now();
+
+    loop {
+        let now = Instant::now();
+        if !paused {
+            position += now - last_update;
+        }
+        last_update = now;
+        while !paused && next < steps.len() && steps[next].at_ms <= position.as_millis() as u64 {
+            game.apply(steps[next].step.clone());
+            next += 1;
+        }
+
+        hud.playback = Some(if next == steps.len() {
+            "END"
+        } else if paused {
+            "PAUSED"
+        } else {
+            "REPLAY"
+        });
+        screen.draw(&game, &hud)?;
+
+        let wait = match steps.get(next) {
+            Some(step) if !paused => {
+                Duration::from_millis(step.at_ms).saturating_sub(position).min(FRAME)
+            }
+            _ => FRAME,
+        };
+        let key = match rx.recv_timeout(wait) {
+            Ok(AppEvent::Terminal(Event::Key(key))) if key.kind == KeyEventKind::Press => key,
+            Ok(AppEvent::Terminal(Event::Resize(width, height))) => {
+                screen.resize(width, height)?;
+                continue;
+            }
+            Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char(' ') | KeyCode::Char('p') => paused = !paused,
+            // The clock entries change nothing on screen, so they go along with the next step.
+            KeyCode::Right | KeyCode::Char('n') if paused => {
+                while let Some(step) = steps.get(next) {
+                    game.apply(step.step.clone());
+                    position = Duration::from_millis(step.at_ms);
+                    next += 1;
+                    if !matches!(step.step, Step::Clock(_)) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -1,135 +1,844 @@
-use std::error::Error;
-use std::io::{stdout, Stdout};
 use std::process::Command;
-use std::sync::mpsc;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode};
-use crossterm::execute;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
-use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind};
 
-use crate::io::spawn_socket_listener;
-use crate::ui::draw_game;
-use crate::Game;
+use crate::io::{
+    copy_to_clipboard, mark_quit, remove_socket, spawn_socket_listener, Query, Spectators,
+};
+use crate::paths::socket_path;
+use crate::game::{Autoplayer, CurrentGame, Feed, GameEvent, Profile, SessionSummary};
+use crate::persist::{self, CommandRow, GameRow, ScoreEntry, WaitEntry};
+use crate::ui::{next_repaint, share_summary, time_phase, Hud, Menu, MenuChoice, Overlay, Theme, TitleMenu, WellArea};
 
-type Term = Terminal<CrosstermBackend<Stdout>>;
+use super::board_art::BoardArt;
+use super::boards::Boards;
+use super::events::{spawn_signal_watcher, AppEvent};
+use super::keys::{action_for, KeyTracker};
+use super::mouse::MouseControl;
+use super::notify::{announce_end, notify_end};
+use super::screen::{focus_pause_enabled, RemoteScreen, Screen, TuiGuard};
+use super::sound::Cues;
+use crate::{CommandEvent, Error, Game};
 
-pub fn run() -> Result<(), Box<dyn Error>> {
+const FRAME: Duration = Duration::from_millis(50);
+// Upper bound on a quiet sleep so clocks on screen still move; longer once nothing is going on.
+const IDLE_WAKEUP: Duration = Duration::from_secs(1);
+const DORMANT_WAKEUP: Duration = Duration::from_secs(5);
+// How often a live run is written out for crash recovery.
+const SNAPSHOT_EVERY: Duration = Duration::from_secs(10);
+// One --autoplay key press per this much play.
+const AUTOPLAY_PACE: Duration = Duration::from_millis(50);
+
+// A profile from the command line is preselected on the title screen. `autoplay` hands the
+// keys to the built-in player, for demos and for leaving the renderer running flat out.
+pub fn run(seed: Option<u64>, profile: Option<Profile>, autoplay: bool) -> Result<(), Error> {
     let mut tui = TuiGuard::new()?;
-    let result = run_loop(tui.terminal_mut());
-    cleanup_tmux_on_exit();
+    let result = run_loop(&mut tui, seed, profile, autoplay);
+    remove_socket();
+    cleanup_session_on_exit();
+    result
+}
+
+// The same game with no terminal of its own: it keeps listening and piling up pieces while
+// `stack-game --attach` UIs come and go. Nothing moves while none is looking.
+pub fn daemon(seed: Option<u64>, profile: Option<Profile>, autoplay: bool) -> Result<(), Error> {
+    println!(
+        "waitris: listening on {}; attach with stack-game --attach",
+        socket_path().display()
+    );
+    let mut screen = RemoteScreen::default();
+    let result = run_loop(&mut screen, seed, profile, autoplay);
+    remove_socket();
     result
 }
 
-fn run_loop(terminal: &mut Term) -> Result<(), Box<dyn Error>> {
-    let mut game = Game::new();
+fn run_loop(
+    screen: &mut impl Screen,
+    seed: Option<u64>,
+    profile: Option<Profile>,
+    autoplay: bool,
+) -> Result<(), Error> {
+    let mut game = seed.map_or_else(Game::new, Game::with_seed);
+    if let Some(profile) = profile {
+        game.profile = profile;
+    }
+    log::info!("seed {}", game.seed);
+    game.start_recording();
+    let keep_streak = keep_streak();
+    if keep_streak && let Some(carried) = persist::load_streak() {
+        game.carry_variety(carried);
+    }
+    if let Some(every) = trickle_every() {
+        game.set_feed(Feed::Trickle { every });
+    }
     let (tx, rx) = mpsc::channel();
-    spawn_socket_listener(tx);
-    let mut last_tick = Instant::now();
+    spawn_socket_listener(tx.clone());
+    spawn_signal_watcher(tx.clone()).map_err(Error::Terminal)?;
+    let mut spectators = Spectators::from_env(tx.clone());
+    screen.spawn_input(tx);
+    let mut hud = Hud::new(persist::load_personal_bests(), Theme::from_env());
+    hud.resume = persist::load_snapshot();
+    hud.high_scores = persist::load_high_scores();
+    hud.longest_waits = persist::load_longest_waits();
+    hud.overlay = if hud.resume.is_some() {
+        Overlay::Resume(Menu::resume())
+    } else {
+        Overlay::Title(TitleMenu::new(game.mode, game.profile))
+    };
+    let mut last_snapshot = Instant::now();
+    let mut mouse = MouseControl::default();
+    let mut keys = KeyTracker::new(screen.reports_key_release());
+    let autopilot_after = autopilot_after();
+    // The autopilot is for while the player is in another pane, so it keeps focus-pause off.
+    let focus_pause = focus_pause_enabled() && autopilot_after.is_none();
+    let mut focus_paused = false;
+    // Until the terminal says otherwise (it may never report focus at all).
+    let mut focused = true;
+    let mut autoplayer = autoplay.then(|| Autoplayer::new(AUTOPLAY_PACE));
+    let mut last_key = Instant::now();
+    let mut well = WellArea::default();
+    let idle_after = screensaver_after();
+    let dim_after = dim_after();
+    let mut last_activity = Instant::now();
+    // Gravity and effect frames advance by real time, independent of how often we draw. Time
+    // only counts from the first update the game spends unfrozen.
+    let mut last_update = None;
+    let mut drained = 0;
+    let mut dirty = true;
+    // What the last drawn frame showed, to skip redraws that would be identical.
+    let mut drawn = None;
+    let game_events = game.subscribe();
+    let cues = Cues::from_env();
+    let mut boards = Boards::from_env();
+    let mut board_art = BoardArt::from_env();
 
-    loop {
-        for ev in rx.try_iter() {
-            game.handle_command_event(ev);
+    'run: loop {
+        // At most one bell a frame, however much happened in it.
+        let mut game_over = false;
+        let mut ring = false;
+        let mut stack_moved = false;
+        for ev in game_events.try_iter() {
+            game_over |= matches!(ev, GameEvent::GameOver { .. });
+            ring |= cues.rings_for(&ev);
+            stack_moved |= BoardArt::changes_on(&ev);
+        }
+        if ring && !hud.muted {
+            screen.bell();
+        }
+        if stack_moved && let Some(art) = &mut board_art {
+            art.publish(boards.main(&game));
+        }
+        // The game-over bookkeeping runs once per finished game, always the main one's: side
+        // boards don't report theirs.
+        if game_over {
+            boards.show_main(&mut game);
+            let new_best = record_best(&mut hud, &game);
+            log::debug!("game over overlay (new best: {new_best})");
+            let rank = record_high_score(&mut hud, &game);
+            record_game(&game);
+            save_replay(&game);
+            persist::clear_snapshot();
+            hud.shared = false;
+            hud.overlay = Overlay::GameOver {
+                menu: Menu::game_over(),
+                new_best,
+                rank,
+            };
         }
 
-        terminal.draw(|frame| draw_game(frame, &game))?;
+        for retired in boards.tidy(&mut game) {
+            hud.totals.add(&retired);
+        }
+        hud.boards = boards.thumbnails();
+        hud.dimmed = dim_after.is_some_and(|after| last_activity.elapsed() >= after);
+        let showing = (game.revision(), time_phase(&game, &hud), hud.dimmed);
+        if dirty || drawn != Some(showing) || hud.debug || hud.overlay == Overlay::Screensaver {
+            drawn = Some(showing);
+            let render_start = Instant::now();
+            well = screen.draw(&game, &hud)?;
+            hud.frames.record(render_start.elapsed(), drained);
+            spectators.broadcast(|| serde_json::to_string(&game).unwrap_or_default());
+        }
 
-        game.process_effects();
+        if idle_after.is_some_and(|after| last_activity.elapsed() >= after)
+            && hud.overlay == Overlay::None
+            && game.board.is_empty()
+            && !game.is_running()
+        {
+            hud.screensaver.restart();
+            hud.overlay = Overlay::Screensaver;
+        }
 
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if matches!(key.code, KeyCode::Char('q')) {
-                    break;
+        // Sleep until the next input, command, gravity tick or animation frame.
+        let mut wait =
+            next_wakeup(&game, &hud, &keys, idle_after, last_activity);
+        if let Some(left) = dim_after.and_then(|after| after.checked_sub(last_activity.elapsed())) {
+            wait = wait.min(left);
+        }
+        if let Some(bot) = &autoplayer
+            && hud.overlay == Overlay::None
+        {
+            wait = wait.min(bot.pace());
+        }
+        let first = match rx.recv_timeout(wait) {
+            Ok(ev) => Some(ev),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let mut suspend = false;
+        // Timers only matter through the game revision and time phase; bare pointer motion
+        // changes nothing on screen.
+        dirty = false;
+        drained = 0;
+        for ev in first.into_iter().chain(rx.try_iter()) {
+            if !matches!(&ev, AppEvent::Terminal(Event::Mouse(m)) if m.kind == MouseEventKind::Moved) {
+                dirty = true;
+            }
+            match ev {
+                AppEvent::Command(ev) => {
+                    drained += 1;
+                    last_activity = Instant::now();
+                    if hud.overlay == Overlay::Screensaver {
+                        hud.overlay = Overlay::None;
+                    }
+                    if let CommandEvent::Start { command, .. } = &ev {
+                        hud.screensaver.feed(command);
+                    }
+                    // A command arriving on the title screen starts the selected mode.
+                    if let Overlay::Title(menu) = hud.overlay {
+                        game.start(menu.mode(), menu.profile());
+                        hud.overlay = Overlay::None;
+                    }
+                    let board = boards.board_for(&mut game, &ev);
+                    if let Some((id, exit_code)) = apply_command(ev, board, &mut hud) {
+                        announce_end(screen, board, focused, id, exit_code);
+                    }
+                }
+                AppEvent::Query(query) => answer_query(query, boards.main(&game), &hud),
+                AppEvent::Shutdown => {
+                    log::info!("shutdown signal");
+                    break 'run;
+                }
+                AppEvent::Quit => {
+                    log::info!("quit requested over the socket");
+                    mark_quit();
+                    break 'run;
+                }
+                AppEvent::Suspend => suspend = true,
+                AppEvent::Attach(client) => screen.attach(client),
+                AppEvent::Detach(id) => screen.detach(id),
+                AppEvent::Spectator(spectator) => spectators.join(spectator),
+                AppEvent::Terminal(Event::Key(key))
+                    if key.code == KeyCode::Char('z')
+                        && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    if key.kind == KeyEventKind::Press {
+                        suspend = true;
+                    }
+                }
+                // Repaint from scratch so a pane resize never leaves stale cells behind.
+                AppEvent::Terminal(Event::Resize(width, height)) => {
+                    log::debug!("resize {width}x{height}");
+                    screen.resize(width, height)?;
+                }
+                // Looking at another pane pauses the game; coming back resumes only our own pause.
+                AppEvent::Terminal(Event::FocusLost) => {
+                    focused = false;
+                    keys.release_all();
+                    if focus_pause && hud.overlay == Overlay::None && !game.game_over {
+                        log::debug!("focus lost: pausing");
+                        hud.overlay = Overlay::Paused(Menu::pause());
+                        focus_paused = true;
+                    }
+                }
+                AppEvent::Terminal(Event::FocusGained) => {
+                    focused = true;
+                    if focus_paused && matches!(hud.overlay, Overlay::Paused(_)) {
+                        hud.overlay = Overlay::None;
+                    }
+                    focus_paused = false;
+                }
+                AppEvent::Terminal(ev) => {
+                    last_activity = Instant::now();
+                    let flow = match ev {
+                        // Waking the screensaver swallows the event.
+                        _ if hud.overlay == Overlay::Screensaver => {
+                            hud.overlay = Overlay::None;
+                            Flow::Continue
+                        }
+                        Event::Key(key) if keys.accept(key) => {
+                            // Any key takes the wheel back from the autopilot.
+                            last_key = Instant::now();
+                            hud.autopilot = false;
+                            handle_key(key.code, &mut game, &mut hud)
+                        }
+                        Event::Mouse(ev) if !hud.overlay.freezes_game() => {
+                            mouse.handle(ev, &mut game, &well);
+                            Flow::Continue
+                        }
+                        _ => Flow::Continue,
+                    };
+                    match flow {
+                        Flow::Continue => {}
+                        Flow::NextBoard => boards.switch(&mut game),
+                        Flow::Quit => {
+                            mark_quit();
+                            break 'run;
+                        }
+                        Flow::Detach => {
+                            mark_quit();
+                            boards.show_main(&mut game);
+                            let running = game.active_run_count();
+                            log::info!("detached with {running} command(s) running");
+                            screen.leave();
+                            listen_headless(&rx, &mut game, &mut hud);
+                            break 'run;
+                        }
+                    }
                 }
-                handle_input(key.code, &mut game);
             }
         }
 
-        if last_tick.elapsed() >= Duration::from_millis(450) {
-            game.tick_gravity();
-            last_tick = Instant::now();
+        if suspend {
+            log::info!("suspending");
+            screen.suspend()?;
+            log::info!("resumed");
+            // Time spent stopped shouldn't count; come back paused.
+            last_update = None;
+            keys.release_all();
+            if hud.overlay == Overlay::None && !game.game_over {
+                hud.overlay = Overlay::Paused(Menu::pause());
+            }
+        }
+
+        if !screen.attached() && hud.overlay == Overlay::None && !game.game_over {
+            hud.overlay = Overlay::Paused(Menu::pause());
+        }
+
+        let elapsed = last_update.map_or(Duration::ZERO, |at: Instant| at.elapsed());
+        last_update = (!hud.overlay.freezes_game()).then(Instant::now);
+        if hud.overlay.freezes_game() {
+            game.reset_timers();
+        } else {
+            if hud.overlay == Overlay::None {
+                for code in keys.due_repeats() {
+                    handle_input(code, &mut game);
+                }
+            }
+            hud.autopilot = autopilot_after.is_some_and(|after| last_key.elapsed() >= after);
+            if hud.autopilot {
+                game.steer();
+            }
+            let action = match &mut autoplayer {
+                Some(bot) if hud.overlay == Overlay::None => bot.next_action(&game, elapsed),
+                _ => None,
+            };
+            hud.autopilot |= autoplayer.is_some();
+            game.step(action, elapsed);
+            let main = boards.main(&game);
+            if last_snapshot.elapsed() >= SNAPSHOT_EVERY && main.worth_saving() {
+                save_snapshot(main);
+                last_snapshot = Instant::now();
+            }
+        }
+    }
+    boards.show_main(&mut game);
+    record_best(&mut hud, &game);
+    if let Err(e) = persist::save_session_summary(&session_summary(&game, &hud)) {
+        log::warn!("saving session summary: {e}");
+    }
+    // A game that ended saved its replay then; one cut short by quitting saves it now.
+    if !game.game_over {
+        save_replay(&game);
+        record_game(&game);
+    }
+    if keep_streak && let Err(e) = persist::save_streak(&game.variety()) {
+        log::warn!("saving streak: {e}");
+    }
+    // Quit or killed, a live game is kept for the next launch to offer back. An unanswered
+    // resume prompt leaves the old save alone.
+    if hud.resume.is_none() {
+        if game.worth_saving() {
+            save_snapshot(&game);
+        } else {
+            persist::clear_snapshot();
         }
     }
     Ok(())
 }
 
-struct TuiGuard {
-    terminal: Term,
+// STACK_SCREENSAVER sets the idle minutes before the screensaver starts; 0 disables it.
+fn screensaver_after() -> Option<Duration> {
+    let minutes = std::env::var("STACK_SCREENSAVER")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(5);
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
 }
 
-impl TuiGuard {
-    fn new() -> Result<Self, Box<dyn Error>> {
-        enable_raw_mode()?;
-        let mut stdout = stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-        terminal.hide_cursor()?;
-        Ok(Self { terminal })
+// Sleep only as long as nothing on screen would change: menus and overlays are static, and a
+// quiet board only needs its blink and clock updates.
+fn next_wakeup(
+    game: &Game,
+    hud: &Hud,
+    keys: &KeyTracker,
+    idle_after: Option<Duration>,
+    last_activity: Instant,
+) -> Duration {
+    let live = !hud.overlay.freezes_game() && !game.game_over;
+    let mut wait = if live || game.is_running() { IDLE_WAKEUP } else { DORMANT_WAKEUP };
+    if live {
+        wait = wait.min(game.until_next_tick());
+        if let Some(repeat) = keys.until_next_repeat() {
+            wait = wait.min(repeat);
+        }
     }
+    if hud.overlay == Overlay::Screensaver {
+        wait = wait.min(FRAME);
+    }
+    if let Some(repaint) = next_repaint(game, hud) {
+        wait = wait.min(repaint);
+    }
+    if let Some(after) = idle_after
+        && hud.overlay == Overlay::None
+        && let Some(left) = after.checked_sub(last_activity.elapsed())
+    {
+        wait = wait.min(left);
+    }
+    wait
+}
+
+// STACK_DIM=<seconds> of no keys and no commands before the UI dims; 0 disables it.
+fn dim_after() -> Option<Duration> {
+    let secs = std::env::var("STACK_DIM")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(60);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+// STACK_STREAK=0 starts every session with a fresh variety streak.
+fn keep_streak() -> bool {
+    std::env::var("STACK_STREAK").unwrap_or_default() != "0"
+}
 
-    fn terminal_mut(&mut self) -> &mut Term {
-        &mut self.terminal
+// STACK_TRICKLE=<seconds>: a running command deals its chunks once, then a filler heartbeat
+// that often until it ends, instead of dealing its chunks again.
+fn trickle_every() -> Option<Duration> {
+    let secs = std::env::var("STACK_TRICKLE").ok()?.parse::<u64>().ok()?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+// STACK_AUTOPILOT=<seconds>: after that long without a key press, keep the stack alive
+// while the player works in the other pane. Off unless set.
+fn autopilot_after() -> Option<Duration> {
+    let secs = std::env::var("STACK_AUTOPILOT").ok()?.parse::<u64>().ok()?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+fn save_replay(game: &Game) {
+    if let Some(recording) = game.recording()
+        && let Err(e) = persist::save_replay(recording)
+    {
+        log::warn!("saving replay: {e}");
     }
 }
 
-impl Drop for TuiGuard {
-    fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
-        let _ = self.terminal.show_cursor();
+fn save_snapshot(game: &Game) {
+    if let Err(e) = persist::save_snapshot(game) {
+        log::warn!("saving snapshot: {e}");
     }
 }
 
-fn handle_input(code: KeyCode, game: &mut Game) {
-    match code {
-        KeyCode::Left => {
-            let _ = game.move_current(-1, 0);
-        }
-        KeyCode::Right => {
-            let _ = game.move_current(1, 0);
-        }
-        KeyCode::Down => {
-            let _ = game.move_current(0, 1);
-        }
-        KeyCode::Up => {
-            let _ = game.rotate_current();
+// True when the game set a new personal best for its mode.
+fn record_best(hud: &mut Hud, game: &Game) -> bool {
+    hud.session_best = hud.session_best.max(game.score);
+    let improved = hud.bests.record(game);
+    if improved && let Err(e) = persist::save_personal_bests(&hud.bests) {
+        log::warn!("saving personal bests: {e}");
+    }
+    improved
+}
+
+// The session so far, as session.json holds it on exit and a socket QUERY gets it any time.
+fn session_summary(game: &Game, hud: &Hud) -> SessionSummary {
+    let mut totals = hud.totals;
+    totals.add(game);
+    SessionSummary {
+        session_secs: hud.clock.session_time().as_secs(),
+        busy_secs: hud.clock.busy_time().as_secs(),
+        totals,
+        current: CurrentGame::of(game),
+        commands: hud.commands.records(),
+    }
+}
+
+fn record_high_score(hud: &mut Hud, game: &Game) -> Option<usize> {
+    if game.score == 0 {
+        return None;
+    }
+    let entry = ScoreEntry::new(game.score, game.lines_cleared, game.mode, game.stats.summary());
+    let rank = persist::insert_high_score(&mut hud.high_scores, entry)?;
+    log::debug!("high score #{}", rank + 1);
+    if let Err(e) = persist::save_high_scores(&hud.high_scores) {
+        log::warn!("saving high scores: {e}");
+    }
+    Some(rank)
+}
+
+#[derive(PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Quit,
+    Detach,
+    NextBoard,
+}
+
+fn handle_key(code: KeyCode, game: &mut Game, hud: &mut Hud) -> Flow {
+    if code == KeyCode::F(3) {
+        hud.debug = !hud.debug;
+        return Flow::Continue;
+    }
+    let overlay = &mut hud.overlay;
+    match overlay {
+        Overlay::Title(menu) => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('h') => open_high_scores(overlay, &mut hud.behind),
+            KeyCode::Up | KeyCode::Down => menu.switch_row(),
+            KeyCode::Left => menu.cycle(-1),
+            KeyCode::Right => menu.cycle(1),
+            KeyCode::Enter => {
+                game.start(menu.mode(), menu.profile());
+                *overlay = Overlay::None;
+            }
+            _ => {}
+        },
+        Overlay::None => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('p') | KeyCode::Esc => *overlay = Overlay::Paused(Menu::pause()),
+            KeyCode::Char('?') => *overlay = Overlay::Help { scroll: 0 },
+            KeyCode::Char('i') => *overlay = Overlay::inspect(),
+            KeyCode::Char('l') => {
+                hud.legend = !hud.legend;
+                hud.leaderboard = false;
+            }
+            KeyCode::Char('c') => {
+                hud.leaderboard = !hud.leaderboard;
+                hud.legend = false;
+            }
+            KeyCode::Char('h') => open_high_scores(overlay, &mut hud.behind),
+            KeyCode::Char('H') => *overlay = Overlay::History { scroll: 0 },
+            KeyCode::Char('m') => hud.muted = !hud.muted,
+            KeyCode::Tab => return Flow::NextBoard,
+            _ => handle_input(code, game),
+        },
+        Overlay::Inspect { .. } => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('i') | KeyCode::Esc => *overlay = Overlay::None,
+            KeyCode::Left => overlay.move_cursor(-1, 0),
+            KeyCode::Right => overlay.move_cursor(1, 0),
+            KeyCode::Up => overlay.move_cursor(0, -1),
+            KeyCode::Down => overlay.move_cursor(0, 1),
+            _ => {}
+        },
+        Overlay::Screensaver => *overlay = Overlay::None,
+        Overlay::Help { .. } => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('?') | KeyCode::Esc => *overlay = Overlay::None,
+            KeyCode::Up => overlay.scroll_help(-1),
+            KeyCode::Down => overlay.scroll_help(1),
+            _ => {}
+        },
+        Overlay::History { .. } => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('H') | KeyCode::Esc => *overlay = Overlay::None,
+            KeyCode::Up => overlay.scroll_history(-1, hud.commands.len()),
+            KeyCode::Down => overlay.scroll_history(1, hud.commands.len()),
+            KeyCode::PageUp => overlay.scroll_history(-10, hud.commands.len()),
+            KeyCode::PageDown => overlay.scroll_history(10, hud.commands.len()),
+            _ => {}
+        },
+        Overlay::Resume(menu) => match code {
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Up => menu.up(),
+            KeyCode::Down => menu.down(),
+            KeyCode::Enter => {
+                let saved = hud.resume.take();
+                match (menu.choice(), saved) {
+                    (MenuChoice::Resume, Some(saved)) => {
+                        game.resume(saved);
+                        *overlay = Overlay::None;
+                    }
+                    _ => {
+                        persist::clear_snapshot();
+                        *overlay = Overlay::Title(TitleMenu::new(game.mode, game.profile));
+                    }
+                }
+            }
+            _ => {}
+        },
+        Overlay::HighScores { .. } | Overlay::LongestWaits => match code {
+            KeyCode::Char('q') => {
+                *overlay = hud.behind;
+                return request_quit(game, overlay, &mut hud.behind);
+            }
+            KeyCode::Char('h') | KeyCode::Esc | KeyCode::Enter => *overlay = hud.behind,
+            KeyCode::Tab => {
+                *overlay = match overlay {
+                    Overlay::LongestWaits => Overlay::HighScores { rank: None },
+                    _ => Overlay::LongestWaits,
+                }
+            }
+            _ => {}
+        },
+        Overlay::ConfirmQuit(menu) => match code {
+            KeyCode::Char('q') => return Flow::Quit,
+            KeyCode::Esc => *overlay = hud.behind,
+            KeyCode::Up => menu.up(),
+            KeyCode::Down => menu.down(),
+            KeyCode::Enter => match menu.choice() {
+                MenuChoice::Quit => return Flow::Quit,
+                MenuChoice::Detach => return Flow::Detach,
+                MenuChoice::Resume | MenuChoice::Restart | MenuChoice::HighScores => {
+                    *overlay = hud.behind
+                }
+            },
+            _ => {}
+        },
+        Overlay::Paused(menu) | Overlay::GameOver { menu, .. } => match code {
+            KeyCode::Char('h') => open_high_scores(overlay, &mut hud.behind),
+            KeyCode::Char('q') => return request_quit(game, overlay, &mut hud.behind),
+            KeyCode::Char('p') | KeyCode::Esc if !game.game_over => *overlay = Overlay::None,
+            KeyCode::Char('s') if game.game_over => match copy_to_clipboard(&share_summary(game)) {
+                Ok(()) => hud.shared = true,
+                Err(e) => log::warn!("copying summary: {e}"),
+            },
+            KeyCode::Up => menu.up(),
+            KeyCode::Down => menu.down(),
+            KeyCode::Enter => match menu.choice() {
+                MenuChoice::Resume => *overlay = Overlay::None,
+                MenuChoice::Restart => {
+                    *overlay = Overlay::None;
+                    record_best(hud, game);
+                    hud.totals.add(game);
+                    if !game.game_over {
+                        record_game(game);
+                    }
+                    game.restart();
+                }
+                MenuChoice::Quit => return request_quit(game, overlay, &mut hud.behind),
+                MenuChoice::Detach => return Flow::Detach,
+                MenuChoice::HighScores => open_high_scores(overlay, &mut hud.behind),
+            },
+            _ => {}
+        },
+    }
+    Flow::Continue
+}
+
+// Opened from a game-over screen, the table highlights where that run landed.
+fn open_high_scores(overlay: &mut Overlay, behind: &mut Overlay) {
+    let rank = match overlay {
+        Overlay::GameOver { rank, .. } => *rank,
+        _ => None,
+    };
+    *behind = *overlay;
+    *overlay = Overlay::HighScores { rank };
+}
+
+// Quitting mid-build would lose those commands' scoring, so ask first.
+fn request_quit(game: &Game, overlay: &mut Overlay, behind: &mut Overlay) -> Flow {
+    if game.active_run_count() == 0 {
+        return Flow::Quit;
+    }
+    *behind = *overlay;
+    *overlay = Overlay::ConfirmQuit(Menu::confirm_quit());
+    Flow::Continue
+}
+
+fn answer_query(query: Query, game: &Game, hud: &Hud) {
+    let json = if query.game {
+        serde_json::to_string(game).unwrap_or_default()
+    } else {
+        // Only read: `waitris --status-line` asks every couple of seconds.
+        serde_json::to_string(&session_summary(game, hud)).unwrap_or_default()
+    };
+    let _ = query.reply.send(json);
+}
+
+// Keep the run's bookkeeping going without a screen until the in-flight commands finish.
+fn listen_headless(rx: &mpsc::Receiver<AppEvent>, game: &mut Game, hud: &mut Hud) {
+    let running = game.active_run_count();
+    println!("waitris: listening until {running} running command(s) finish");
+    while game.active_run_count() > 0 {
+        match rx.recv() {
+            Ok(AppEvent::Command(ev)) => {
+                apply_command(ev, game, hud);
+            }
+            Ok(AppEvent::Query(query)) => answer_query(query, game, hud),
+            Ok(AppEvent::Shutdown | AppEvent::Quit) | Err(_) => break,
+            Ok(_) => {}
         }
-        KeyCode::Char(' ') => {
-            game.hard_drop();
+    }
+}
+
+// Bookkeeping every command event gets, with or without a screen. Returns the run and exit
+// code of a command that just ended.
+fn apply_command(ev: CommandEvent, game: &mut Game, hud: &mut Hud) -> Option<(u64, i32)> {
+    hud.clock.observe(&ev);
+    hud.commands.observe(&ev);
+    let ended = match ev {
+        CommandEvent::End { id, _exit_code } => Some((id, _exit_code)),
+        CommandEvent::Start { .. } | CommandEvent::Output { .. } => None,
+    };
+    game.handle_command_event(ev);
+    if let Some((id, exit_code)) = ended {
+        hud.commands.set_pieces(id, game.pieces_for_run(id));
+        record_command(game, id, exit_code);
+        record_wait(hud, game, id, exit_code);
+        notify_end(game, id, exit_code);
+    }
+    ended
+}
+
+fn record_wait(hud: &mut Hud, game: &Game, id: u64, exit_code: i32) {
+    let (Some(command), Some(duration), Some(recap)) =
+        (game.command_for_run(id), game.run_elapsed(id), game.run_recap(id))
+    else {
+        return;
+    };
+    let entry = WaitEntry::new(command, duration, exit_code, recap);
+    if let Some(rank) = persist::insert_longest_wait(&mut hud.longest_waits, entry) {
+        log::debug!("longest wait #{}", rank + 1);
+        if let Err(e) = persist::save_longest_waits(&hud.longest_waits) {
+            log::warn!("saving longest waits: {e}");
         }
-        _ => {}
     }
 }
 
-fn cleanup_tmux_on_exit() {
-    let managed = std::env::var("STACK_MANAGED").unwrap_or_default() == "1";
-    let kill_session = std::env::var("STACK_KILL_SESSION").unwrap_or_default() == "1";
-    if !managed || std::env::var("TMUX").is_err() {
+fn record_command(game: &Game, id: u64, exit_code: i32) {
+    let (Some(command), Some(identity), Some(duration)) = (
+        game.command_for_run(id),
+        game.identity_for_run(id),
+        game.run_elapsed(id),
+    ) else {
         return;
+    };
+    let row = CommandRow {
+        command,
+        identity,
+        duration,
+        exit_code,
+        pieces: game.pieces_for_run(id),
+    };
+    if let Err(e) = persist::record_command(&row) {
+        log::warn!("recording command: {e}");
     }
+}
+
+// Games that never placed a piece (title screen, instant quit) aren't worth a row.
+fn record_game(game: &Game) {
+    if game.stats.pieces_placed == 0 {
+        return;
+    }
+    let row = GameRow {
+        mode: game.mode,
+        profile: game.profile,
+        score: game.score,
+        lines: game.lines_cleared,
+        pieces: game.stats.pieces_placed,
+        bombs_earned: game.stats.bombs_earned,
+        bombs_used: game.stats.bombs_used,
+        play_time: game.play_time(),
+    };
+    if let Err(e) = persist::record_game(&row) {
+        log::warn!("recording game: {e}");
+    }
+}
+
+fn handle_input(code: KeyCode, game: &mut Game) {
+    if let Some(action) = action_for(code) {
+        game.input(action);
+    }
+}
 
-    if kill_session {
-        if let Ok(session) = tmux_current_session() {
-            let _ = Command::new("tmux")
-                .args(&["kill-session", "-t", &session])
+// Do what the launcher asked for when the game quits (STACK_ON_EXIT): close its pane or
+// window (the default), the whole session, detach from the session, or leave the pane be.
+fn cleanup_session_on_exit() {
+    let managed = std::env::var("STACK_MANAGED").unwrap_or_default() == "1";
+    if !managed {
+        return;
+    }
+    let on_exit = std::env::var("STACK_ON_EXIT").unwrap_or_default();
+    if std::env::var("TMUX").is_err() {
+        if let Ok(session) = std::env::var("STY") {
+            let window = std::env::var("WINDOW").unwrap_or_default();
+            let cmd = match on_exit.as_str() {
+                "kill-session" => "quit",
+                "detach" => "detach",
+                "keep-pane" => return,
+                _ => "kill",
+            };
+            let _ = Command::new("screen")
+                .args(["-S", &session, "-p", &window, "-X", cmd])
                 .status();
         }
-    } else {
-        let _ = Command::new("tmux").args(&["kill-pane"]).status();
+        return;
+    }
+
+    restore_pane_border();
+    match on_exit.as_str() {
+        "kill-session" | "detach" => {
+            let action = if on_exit == "detach" {
+                ["detach-client", "-s"]
+            } else {
+                ["kill-session", "-t"]
+            };
+            if let Ok(session) = tmux_current_session() {
+                let _ = Command::new("tmux").args(action).arg(&session).status();
+            }
+        }
+        "keep-pane" => {}
+        // Without a target tmux picks the active pane, which is usually the shell.
+        _ => {
+            let pane = std::env::var("TMUX_PANE").unwrap_or_default();
+            let _ = Command::new("tmux").args(["kill-pane", "-t", &pane]).status();
+        }
+    }
+}
+
+// Put back the window's border styles from before the launcher coloured them; an empty value
+// was inherited, so it's unset again.
+fn restore_pane_border() {
+    let Ok(previous) = std::env::var("STACK_BORDER_RESTORE") else {
+        return;
+    };
+    let pane = std::env::var("TMUX_PANE").unwrap_or_default();
+    for (option, value) in ["pane-border-style", "pane-active-border-style"]
+        .into_iter()
+        .zip(previous.split('|'))
+    {
+        let mut cmd = Command::new("tmux");
+        if value.is_empty() {
+            cmd.args(["set-option", "-w", "-u", "-t", &pane, option]);
+        } else {
+            cmd.args(["set-option", "-w", "-t", &pane, option, value]);
+        }
+        let _ = cmd.status();
     }
 }
 
-fn tmux_current_session() -> Result<String, Box<dyn Error>> {
+fn tmux_current_session() -> Result<String, Error> {
     let out = Command::new("tmux")
-        .args(&["display-message", "-p", "#S"])
-        .output()?;
+        .args(["display-message", "-p", "#S"])
+        .output()
+        .map_err(|e| Error::Tmux(format!("tmux display-message failed: {e}")))?;
     if out.status.success() {
         let name = String::from_utf8_lossy(&out.stdout).trim().to_string();
         Ok(name)
     } else {
-        Err("tmux display-message failed".into())
+        Err(Error::Tmux("tmux display-message failed".to_string()))
     }
 }
@@ -0,0 +1,364 @@
+use std::io::{stdout, BufWriter, Stdout, Write};
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use ratatui::backend::{CrosstermBackend, TestBackend};
+use ratatui::layout::Rect;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+use signal_hook::consts::SIGTSTP;
+use signal_hook::low_level::emulate_default_handler;
+
+use crate::io::Attach;
+use crate::ui::{draw_game, Hud, WellArea};
+use crate::{Error, Game};
+
+use super::events::{spawn_terminal_reader, AppEvent};
+
+type Term = Terminal<CrosstermBackend<Stdout>>;
+
+// Kitty progressive enhancement: unambiguous keys plus press/repeat/release events.
+const KITTY_FLAGS: KeyboardEnhancementFlags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+    .union(KeyboardEnhancementFlags::REPORT_EVENT_TYPES);
+
+// What the app loop needs from a terminal, so it can run against something other than
+// crossterm (e.g. ratatui's TestBackend for rendering without a tty).
+pub(super) trait Screen {
+    fn draw(&mut self, game: &Game, hud: &Hud) -> Result<WellArea, Error>;
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), Error>;
+    // Give the terminal back for good (e.g. when detaching to listen headlessly).
+    fn leave(&mut self);
+    fn suspend(&mut self) -> Result<(), Error>;
+    // Start feeding terminal events into the loop, if this screen has any.
+    fn spawn_input(&self, tx: mpsc::Sender<AppEvent>);
+    // Ring the terminal bell (BEL), for sound cues.
+    fn bell(&mut self) {
+        self.write_escape("\x07");
+    }
+    // Pass an escape sequence straight to the terminal, outside any frame.
+    fn write_escape(&mut self, _escape: &str) {}
+    // True when key releases are guaranteed (kitty keyboard protocol), enabling held-key repeat.
+    fn reports_key_release(&self) -> bool {
+        false
+    }
+    // A UI attaching over the socket. Only the daemon's screen takes one; the rest turn it away.
+    fn attach(&mut self, client: Attach) {
+        client.reject();
+    }
+    fn detach(&mut self, _id: u64) {}
+    // False while a daemon has no UI attached, which holds the game paused.
+    fn attached(&self) -> bool {
+        true
+    }
+}
+
+// STACK_FOCUS_PAUSE=0 keeps gravity running while the pane is unfocused.
+pub(super) fn focus_pause_enabled() -> bool {
+    std::env::var("STACK_FOCUS_PAUSE").unwrap_or_default() != "0"
+}
+
+// The real terminal: raw mode, alternate screen, mouse and focus reporting while it lives.
+pub(super) struct TuiGuard {
+    terminal: Term,
+    mouse: bool,
+    focus: bool,
+    kitty: bool,
+    entered: bool,
+}
+
+impl TuiGuard {
+    // STACK_KITTY=0 opts out of the kitty keyboard protocol.
+    pub(super) fn new() -> Result<Self, Error> {
+        Self::with_kitty(std::env::var("STACK_KITTY").unwrap_or_default() != "0")
+    }
+
+    pub(super) fn with_kitty(wanted: bool) -> Result<Self, Error> {
+        // STACK_MOUSE=0 leaves the mouse to tmux (e.g. for text selection).
+        let mouse = std::env::var("STACK_MOUSE").unwrap_or_default() != "0";
+        let focus = focus_pause_enabled();
+        let terminal = Terminal::new(CrosstermBackend::new(stdout())).map_err(Error::Terminal)?;
+        let mut tui = Self {
+            terminal,
+            mouse,
+            focus,
+            kitty: false,
+            entered: false,
+        };
+        tui.enter()?;
+        // Ask once, before the input thread starts reading stdin.
+        if wanted && supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(tui.terminal.backend_mut(), PushKeyboardEnhancementFlags(KITTY_FLAGS))
+                .map_err(Error::Terminal)?;
+            tui.kitty = true;
+        }
+        Ok(tui)
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        enable_raw_mode().map_err(Error::Terminal)?;
+        let backend = self.terminal.backend_mut();
+        execute!(backend, EnterAlternateScreen).map_err(Error::Terminal)?;
+        if self.mouse {
+            execute!(backend, EnableMouseCapture).map_err(Error::Terminal)?;
+        }
+        if self.focus {
+            execute!(backend, EnableFocusChange).map_err(Error::Terminal)?;
+        }
+        if self.kitty {
+            execute!(backend, PushKeyboardEnhancementFlags(KITTY_FLAGS)).map_err(Error::Terminal)?;
+        }
+        self.terminal.hide_cursor().map_err(Error::Terminal)?;
+        self.entered = true;
+        Ok(())
+    }
+}
+
+impl Screen for TuiGuard {
+    fn draw(&mut self, game: &Game, hud: &Hud) -> Result<WellArea, Error> {
+        let mut well = WellArea::default();
+        self.terminal.draw(|frame| well = draw_game(frame, game, hud)).map_err(Error::Terminal)?;
+        Ok(well)
+    }
+
+    // Repaint from scratch so a pane resize never leaves stale cells behind.
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        self.terminal.resize(Rect::new(0, 0, width, height)).map_err(Error::Terminal)?;
+        self.terminal.clear().map_err(Error::Terminal)?;
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        if !std::mem::take(&mut self.entered) {
+            return;
+        }
+        if self.kitty {
+            let _ = execute!(self.terminal.backend_mut(), PopKeyboardEnhancementFlags);
+        }
+        let _ = disable_raw_mode();
+        if self.mouse {
+            let _ = execute!(self.terminal.backend_mut(), DisableMouseCapture);
+        }
+        if self.focus {
+            let _ = execute!(self.terminal.backend_mut(), DisableFocusChange);
+        }
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+
+    // Hand the terminal back to the shell, stop like a normal job, and repaint on `fg`.
+    fn suspend(&mut self) -> Result<(), Error> {
+        self.leave();
+        emulate_default_handler(SIGTSTP).map_err(Error::Terminal)?;
+        self.enter()?;
+        self.terminal.clear().map_err(Error::Terminal)?;
+        Ok(())
+    }
+
+    fn spawn_input(&self, tx: mpsc::Sender<AppEvent>) {
+        spawn_terminal_reader(tx);
+    }
+
+    fn write_escape(&mut self, escape: &str) {
+        let backend = self.terminal.backend_mut();
+        let _ = backend.write_all(escape.as_bytes()).and_then(|()| backend.flush());
+    }
+
+    fn reports_key_release(&self) -> bool {
+        self.kitty
+    }
+}
+
+impl Drop for TuiGuard {
+    fn drop(&mut self) {
+        self.leave();
+    }
+}
+
+// Off-screen buffer of a fixed size; `contents` returns the last frame as plain text.
+pub(super) struct TestScreen {
+    terminal: Terminal<TestBackend>,
+}
+
+impl TestScreen {
+    pub(super) fn new(width: u16, height: u16) -> Result<Self, Error> {
+        Ok(Self {
+            terminal: Terminal::new(TestBackend::new(width, height)).map_err(Error::Terminal)?,
+        })
+    }
+
+    pub(super) fn contents(&self) -> String {
+        let buffer = self.terminal.backend().buffer();
+        let width = buffer.area.width as usize;
+        buffer
+            .content
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// One frame drawn off screen, as the text snapshot tests compare.
+pub(crate) fn render_frame(
+    game: &Game,
+    hud: &Hud,
+    width: u16,
+    height: u16,
+) -> Result<String, Error> {
+    let mut screen = TestScreen::new(width, height)?;
+    screen.draw(game, hud)?;
+    Ok(screen.contents())
+}
+
+impl Screen for TestScreen {
+    fn draw(&mut self, game: &Game, hud: &Hud) -> Result<WellArea, Error> {
+        let mut well = WellArea::default();
+        self.terminal.draw(|frame| well = draw_game(frame, game, hud)).map_err(Error::Terminal)?;
+        Ok(well)
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        self.terminal.backend_mut().resize(width, height);
+        self.terminal.resize(Rect::new(0, 0, width, height)).map_err(Error::Terminal)?;
+        Ok(())
+    }
+
+    fn leave(&mut self) {}
+
+    fn suspend(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn spawn_input(&self, _tx: mpsc::Sender<AppEvent>) {}
+}
+
+type RemoteTerm = Terminal<CrosstermBackend<BufWriter<UnixStream>>>;
+
+// A client that stops reading for this long is let go rather than stalling the game.
+const REMOTE_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// The daemon's screen: draws into whichever UI is attached over the socket, one at a time, and
+// nowhere while none is. Input arrives from the socket listener, not from here.
+#[derive(Default)]
+pub(super) struct RemoteScreen {
+    client: Option<RemoteClient>,
+}
+
+struct RemoteClient {
+    id: u64,
+    terminal: RemoteTerm,
+    // Kept to hang up on the UI, which also ends its reader in the socket listener.
+    stream: UnixStream,
+}
+
+impl RemoteScreen {
+    fn drop_client(&mut self) {
+        let Some(mut client) = self.client.take() else {
+            return;
+        };
+        log::info!("ui {} detached", client.id);
+        // A terminal dropped with its cursor hidden shows it again, and complains on stderr if
+        // the UI has already gone; this one's screen isn't ours to fix up.
+        let shown = client.terminal.show_cursor().is_ok();
+        let _ = client.stream.shutdown(Shutdown::Both);
+        if !shown {
+            std::mem::forget(client.terminal);
+        }
+    }
+}
+
+// Answer OK and size a terminal to the UI's window.
+fn open_remote(stream: &mut UnixStream, width: u16, height: u16) -> std::io::Result<RemoteTerm> {
+    writeln!(stream, "OK")?;
+    let writer = stream.try_clone()?;
+    let options = TerminalOptions {
+        viewport: Viewport::Fixed(Rect::new(0, 0, width, height)),
+    };
+    let mut terminal =
+        Terminal::with_options(CrosstermBackend::new(BufWriter::new(writer)), options)?;
+    terminal.clear()?;
+    Ok(terminal)
+}
+
+impl Screen for RemoteScreen {
+    fn draw(&mut self, game: &Game, hud: &Hud) -> Result<WellArea, Error> {
+        let mut well = WellArea::default();
+        if let Some(client) = &mut self.client
+            && client.terminal.draw(|frame| well = draw_game(frame, game, hud)).is_err()
+        {
+            self.drop_client();
+        }
+        Ok(well)
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        if let Some(client) = &mut self.client
+            && (client.terminal.resize(Rect::new(0, 0, width, height)).is_err()
+                || client.terminal.clear().is_err())
+        {
+            self.drop_client();
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.drop_client();
+    }
+
+    // Ctrl-Z in an attached UI detaches it.
+    fn suspend(&mut self) -> Result<(), Error> {
+        self.drop_client();
+        Ok(())
+    }
+
+    fn spawn_input(&self, _tx: mpsc::Sender<AppEvent>) {}
+
+    // Bells and notifications go to the attached UI's terminal, not the daemon's.
+    fn write_escape(&mut self, escape: &str) {
+        if let Some(client) = &mut self.client {
+            let backend = client.terminal.backend_mut();
+            if backend.write_all(escape.as_bytes()).and_then(|()| backend.flush()).is_err() {
+                self.drop_client();
+            }
+        }
+    }
+
+    // The newest UI wins; one already attached is let go.
+    fn attach(&mut self, client: Attach) {
+        self.drop_client();
+        let Attach { id, mut stream, width, height } = client;
+        let _ = stream.set_write_timeout(Some(REMOTE_WRITE_TIMEOUT));
+        match open_remote(&mut stream, width, height) {
+            Ok(terminal) => {
+                log::info!("ui {id} attached at {width}x{height}");
+                self.client = Some(RemoteClient { id, terminal, stream });
+            }
+            // Shut, not just dropped: the socket's reader holds a clone that would keep
+            // passing the UI's input on.
+            Err(e) => {
+                log::warn!("ui {id} went away while attaching: {e}");
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+
+    fn detach(&mut self, id: u64) {
+        if self.client.as_ref().is_some_and(|client| client.id == id) {
+            self.drop_client();
+        }
+    }
+
+    fn attached(&self) -> bool {
+        self.client.is_some()
+    }
+}
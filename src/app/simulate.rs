@@ -0,0 +1,60 @@
+use std::fs;
+
+use crate::game::Cell;
+use crate::persist::PersonalBests;
+use crate::testing::{run_script, tick};
+use crate::ui::{Hud, Theme};
+use crate::{Error, Game};
+
+use super::screen::render_frame;
+
+// Size of the off-screen frame printed by --render.
+const RENDER_W: u16 = 50;
+const RENDER_H: u16 = 40;
+
+// Gravity ticks allowed after the script ends; commands left running re-deal forever.
+const MAX_DRAIN_TICKS: u32 = 10_000;
+
+// Run a script of socket lines (START/END) plus TICK [n] and LEFT/RIGHT/DOWN/ROTATE/DROP
+// against the game logic with no terminal, then print the result. `render` prints the final
+// frame as the UI would draw it instead of the bare board.
+pub fn simulate(path: &str, render: bool, seed: Option<u64>) -> Result<(), Error> {
+    let script = fs::read_to_string(path).map_err(|e| Error::Config(format!("{path}: {e}")))?;
+    let mut game = seed.map_or_else(Game::new, Game::with_seed);
+    game.start(game.mode, game.profile);
+
+    run_script(&mut game, &script).map_err(|e| Error::Config(format!("{path}:{e}")))?;
+
+    let mut ticks = 0;
+    while game.is_running() && !game.game_over && ticks < MAX_DRAIN_TICKS {
+        tick(&mut game);
+        ticks += 1;
+    }
+
+    if render {
+        let hud = Hud::new(PersonalBests::default(), Theme::default());
+        println!("{}", render_frame(&game, &hud, RENDER_W, RENDER_H)?);
+    } else {
+        print_result(&game);
+    }
+    Ok(())
+}
+
+fn print_result(game: &Game) {
+    println!("score {}", game.score);
+    println!("lines {}", game.lines_cleared);
+    println!("over  {}", game.game_over);
+    for y in 0..game.board.height {
+        let row: String = game
+            .board
+            .row(y)
+            .iter()
+            .map(|cell| match *cell {
+                Cell::Filled(left, right, _) => format!("{left}{right}"),
+                Cell::Empty => "  ".to_string(),
+            })
+            .collect();
+        println!("|{row}|");
+    }
+    println!("+{}+", "-".repeat(game.board.width * 2));
+}
@@ -0,0 +1,47 @@
+use crate::game::GameEvent;
+
+// Which game events ring the terminal bell. STACK_BELL=all rings for every one, or name some:
+// STACK_BELL=clear,bomb,garbage,over. Off unless set; `m` mutes it for the session.
+#[derive(Default)]
+pub(super) struct Cues {
+    clear: bool,
+    bomb: bool,
+    garbage: bool,
+    over: bool,
+}
+
+impl Cues {
+    pub(super) fn from_env() -> Self {
+        let setting = std::env::var("STACK_BELL").unwrap_or_default();
+        let mut cues = Cues::default();
+        for name in setting.split(',').map(str::trim) {
+            match name {
+                "all" | "1" => {
+                    cues = Cues {
+                        clear: true,
+                        bomb: true,
+                        garbage: true,
+                        over: true,
+                    }
+                }
+                "clear" => cues.clear = true,
+                "bomb" => cues.bomb = true,
+                "garbage" => cues.garbage = true,
+                "over" => cues.over = true,
+                "" | "0" => {}
+                other => log::warn!("STACK_BELL: unknown cue {other:?}"),
+            }
+        }
+        cues
+    }
+
+    pub(super) fn rings_for(&self, ev: &GameEvent) -> bool {
+        match ev {
+            GameEvent::LinesCleared { .. } => self.clear,
+            GameEvent::BombEarned { .. } => self.bomb,
+            GameEvent::GarbageAdded { .. } => self.garbage,
+            GameEvent::GameOver { .. } => self.over,
+            GameEvent::PieceLocked { .. } => false,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{BufRead, BufReader, Read};
+use std::sync::mpsc::{self, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+
+use crate::io::connect_spectator;
+use crate::paths::watch_socket_path;
+use crate::persist::PersonalBests;
+use crate::ui::{Hud, Theme};
+use crate::{Error, Game};
+
+use super::events::AppEvent;
+use super::screen::{Screen, TuiGuard};
+
+const FRAME: Duration = Duration::from_millis(50);
+
+// A read-only view of a game started with STACK_WATCH: the local one's watch socket, or
+// `host:port` for one listening on TCP. Keys only reach this viewer; q quits.
+pub fn watch(addr: Option<&str>) -> Result<(), Error> {
+    let stream = connect_spectator(addr).map_err(|e| {
+        let place = addr.map_or_else(|| watch_socket_path().display().to_string(), str::to_string);
+        Error::Socket(format!("no game to watch on {place}: {e}"))
+    })?;
+    let mut tui = TuiGuard::with_kitty(false)?;
+    view(&mut tui, stream)
+}
+
+fn view(screen: &mut impl Screen, stream: Box<dyn Read + Send>) -> Result<(), Error> {
+    let (tx, rx) = mpsc::channel();
+    screen.spawn_input(tx);
+    let (frame_tx, frames) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            match serde_json::from_str::<Game>(&line) {
+                Ok(game) => {
+                    if frame_tx.send(game).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => log::warn!("bad watch frame: {e}"),
+            }
+        }
+    });
+    let mut hud = Hud::new(PersonalBests::default(), Theme::from_env());
+    hud.playback = Some("LIVE");
+    let mut game = None;
+    let mut dirty = true;
+
+    loop {
+        // Only the newest frame matters.
+        loop {
+            match frames.try_recv() {
+                Ok(next) => {
+                    game = Some(next);
+                    dirty = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                // The game went away; its last frame stays up.
+                Err(TryRecvError::Disconnected) => {
+                    if hud.playback != Some("ENDED") {
+                        hud.playback = Some("ENDED");
+                        dirty = true;
+                    }
+                    break;
+                }
+            }
+        }
+        if dirty && let Some(game) = &game {
+            screen.draw(game, &hud)?;
+            dirty = false;
+        }
+
+        match rx.recv_timeout(FRAME) {
+            Ok(AppEvent::Terminal(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    break;
+                }
+            }
+            Ok(AppEvent::Terminal(Event::Resize(width, height))) => {
+                screen.resize(width, height)?;
+                dirty = true;
+            }
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Ok(())
+}
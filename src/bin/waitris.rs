@@ -1,278 +1,88 @@
 use std::env;
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::process::{Command, ExitCode};
+use std::process::ExitCode;
 
-const HOOK_SOURCE: &str = include_str!("../../scripts/stack-hook.sh");
+use stack_game::launcher::{
+    attach_game, game_status, guard_game, install_hook, publish_status_line, quit_session,
+    run_launcher, show_stats, stop_game, toggle_game, uninstall_hook, watch_game, Backend,
+    OnExit, Options, Shell, Split, PROFILES,
+};
 
-fn main() -> ExitCode {
-    let mut args = env::args().skip(1);
-    match args.next().as_deref() {
-        None => run_launcher(),
-        Some("quit") => quit_session(),
-        Some("install-hook") => install_hook(),
-        Some("uninstall-hook") => uninstall_hook(),
-        _ => {
-            eprintln!("usage: waitris [quit|install-hook|uninstall-hook]");
-            ExitCode::from(2)
-        }
-    }
-}
-
-fn run_launcher() -> ExitCode {
-    let pane_w = env::var("STACK_PANE_W")
-        .ok()
-        .and_then(|s| s.parse::<u16>().ok())
-        .unwrap_or(24);
-
-    if !tmux_available() {
-        eprintln!("tmux not found on PATH. Please install tmux to use waitris.");
-        return ExitCode::from(1);
-    }
-
-    let game_cmd = match game_binary_path() {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("cannot find stack-game binary: {e}");
-            return ExitCode::from(1);
-        }
-    };
-
-    let result = if env::var("TMUX").is_ok() {
-        run_inside_tmux(pane_w, &game_cmd)
-    } else {
-        run_new_tmux_session(pane_w, &game_cmd)
-    };
-
-    if let Err(err) = result {
-        eprintln!("waitris launcher error: {err}");
-        ExitCode::from(1)
-    } else {
-        ExitCode::SUCCESS
-    }
-}
+const USAGE: &str = "usage: waitris [--split right|left|bottom] [--backend tmux|screen] [--no-tmux] [--kill-session|--keep-pane|--detach-on-quit] [--border-color <colour>] [--status-line] [--guard] [--per-session] [--profile casual|normal|hardcore] [--config <file>] [--game-bin <path>] [start|stop|status|toggle|mini|guard|attach|watch [<host:port>]|quit|install-hook [bash|zsh]|uninstall-hook|stats [summary --period day|week|month | export <file> | import <file>]]";
 
-fn tmux_available() -> bool {
-    Command::new("tmux")
-        .arg("-V")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
 }
 
-fn run_inside_tmux(pane_w: u16, game_cmd: &str) -> Result<(), String> {
-    let pct = percent_for_width(pane_w);
-    let status = Command::new("tmux")
-        .args(&[
-            "split-window",
-            "-h",
-            "-p",
-            &pct,
-            "env",
-            "STACK_MANAGED=1",
-            "STACK_KILL_SESSION=0",
-            game_cmd,
-        ])
-        .status()
-        .map_err(|e| format!("tmux split failed: {e}"))?;
-    if !status.success() {
-        return Err(format!("tmux split failed with status {}", status));
-    }
-    if let Ok(session) = current_session_name() {
-        apply_session_options(&session);
-    }
-    let _ = Command::new("tmux").args(&["select-pane", "-L"]).status();
-    Ok(())
-}
-
-fn run_new_tmux_session(pane_w: u16, game_cmd: &str) -> Result<(), String> {
-    let pct = percent_for_width(pane_w);
-    let shell = env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
-    let session = format!("waitris-{}", std::process::id());
-    let status = Command::new("tmux")
-        .args(&["-f", "/dev/null", "new-session", "-d", "-s", &session, &shell])
-        .status()
-        .map_err(|e| format!("tmux new-session failed: {e}"))?;
-    if !status.success() {
-        return Err(format!("tmux new-session failed with status {}", status));
-    }
-    apply_session_options(&session);
-    let status = Command::new("tmux")
-        .args(&[
-            "split-window",
-            "-h",
-            "-p",
-            &pct,
-            "env",
-            "STACK_MANAGED=1",
-            "STACK_KILL_SESSION=1",
-            game_cmd,
-        ])
-        .status()
-        .map_err(|e| format!("tmux split failed: {e}"))?;
-    if !status.success() {
-        return Err(format!("tmux split failed with status {}", status));
-    }
-    let _ = Command::new("tmux").args(&["select-pane", "-L"]).status();
-    let _ = Command::new("tmux")
-        .args(&["attach-session", "-t", &session])
-        .status();
-    Ok(())
-}
-
-fn apply_session_options(session: &str) {
-    let _ = Command::new("tmux")
-        .args(&["set", "-t", session, "status", "off"])
-        .status();
-    let _ = Command::new("tmux")
-        .args(&["set", "-t", session, "pane-border-status", "off"])
-        .status();
-    let _ = Command::new("tmux")
-        .args(&["set", "-t", session, "display-panes-time", "1"])
-        .status();
-    let _ = Command::new("tmux")
-        .args(&["set-hook", "-t", session, "pane-exited", "kill-session"])
-        .status();
-    let _ = Command::new("tmux")
-        .args(&["bind-key", "-n", "C-Space", "select-pane", "-t", ":.+"])
-        .status();
-}
-
-fn quit_session() -> ExitCode {
-    if env::var("TMUX").is_err() {
-        eprintln!("waitris quit must be run inside tmux");
-        return ExitCode::from(1);
-    }
-    let session = match current_session_name() {
-        Ok(s) if !s.is_empty() => s,
-        _ => return ExitCode::from(1),
-    };
-    let status = Command::new("tmux")
-        .args(&["kill-session", "-t", &session])
-        .status();
-    match status {
-        Ok(s) if s.success() => ExitCode::SUCCESS,
-        Ok(s) => ExitCode::from(s.code().unwrap_or(1) as u8),
-        Err(_) => ExitCode::from(1),
-    }
-}
-
-fn install_hook() -> ExitCode {
-    let hook_path = match hook_path() {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("waitris install-hook: {e}");
-            return ExitCode::from(1);
-        }
-    };
-
-    if let Err(e) = fs::create_dir_all(hook_path.parent().unwrap_or_else(|| Path::new("/"))) {
-        eprintln!("waitris install-hook: {e}");
-        return ExitCode::from(1);
-    }
-    if let Err(e) = fs::write(&hook_path, HOOK_SOURCE) {
-        eprintln!("waitris install-hook: {e}");
-        return ExitCode::from(1);
-    }
-
-    let source_line = format!("source {}", hook_path.display());
-    let _ = ensure_rc_line(".zshrc", &source_line);
-    let _ = ensure_rc_line(".bashrc", &source_line);
-
-    ExitCode::SUCCESS
-}
-
-fn uninstall_hook() -> ExitCode {
-    let hook_path = match hook_path() {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("waitris uninstall-hook: {e}");
-            return ExitCode::from(1);
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+    let mut opts = Options::default();
+    while let Some(flag) = args.next_if(|a| a.starts_with('-')) {
+        match flag.as_str() {
+            "--split" => match args.next().as_deref().and_then(Split::parse) {
+                Some(s) => opts.split = s,
+                None => return usage(),
+            },
+            "--backend" => match args.next().as_deref().and_then(Backend::parse) {
+                Some(b) => opts.backend = Some(b),
+                None => return usage(),
+            },
+            "--kill-session" => opts.on_exit = Some(OnExit::KillSession),
+            "--keep-pane" => opts.on_exit = Some(OnExit::KeepPane),
+            "--detach-on-quit" => opts.on_exit = Some(OnExit::Detach),
+            "--border-color" => match args.next() {
+                Some(colour) => opts.border = Some(colour),
+                None => return usage(),
+            },
+            "--status-line" => opts.status_line = true,
+            "--guard" => opts.guard = true,
+            "--per-session" => opts.per_session = true,
+            "--game-bin" => match args.next() {
+                Some(path) => opts.game_bin = Some(path),
+                None => return usage(),
+            },
+            "--profile" => match args.next() {
+                Some(p) if PROFILES.contains(&p.to_ascii_lowercase().as_str()) => {
+                    opts.profile = Some(p.to_ascii_lowercase())
+                }
+                _ => return usage(),
+            },
+            // The guard re-reads the file from another directory, so it's made absolute.
+            "--config" => match args
+                .next()
+                .map(|p| fs::canonicalize(&p).map_err(|e| (p, e)))
+            {
+                Some(Ok(path)) => opts.config = Some(path),
+                Some(Err((path, e))) => {
+                    eprintln!("waitris: {path}: {e}");
+                    return ExitCode::from(2);
+                }
+                None => return usage(),
+            },
+            "--no-tmux" => opts.no_tmux = true,
+            _ => return usage(),
         }
-    };
-
-    let source_line = format!("source {}", hook_path.display());
-    let _ = remove_rc_line(".zshrc", &source_line);
-    let _ = remove_rc_line(".bashrc", &source_line);
-    let _ = fs::remove_file(&hook_path);
-
-    ExitCode::SUCCESS
-}
-
-fn hook_path() -> Result<PathBuf, String> {
-    let home = env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    Ok(Path::new(&home)
-        .join(".config")
-        .join("waitris")
-        .join("stack-hook.sh"))
-}
-
-fn ensure_rc_line(rc_name: &str, line: &str) -> Result<(), String> {
-    let path = rc_path(rc_name)?;
-    let mut contents = fs::read_to_string(&path).unwrap_or_default();
-    if contents.lines().any(|l| l.trim() == line) {
-        return Ok(());
-    }
-    if !contents.ends_with('\n') && !contents.is_empty() {
-        contents.push('\n');
     }
-    contents.push_str(line);
-    contents.push('\n');
-    fs::write(&path, contents).map_err(|e| e.to_string())?;
-    Ok(())
-}
-
-fn remove_rc_line(rc_name: &str, line: &str) -> Result<(), String> {
-    let path = rc_path(rc_name)?;
-    let contents = match fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(_) => return Ok(()),
-    };
-    let filtered: Vec<&str> = contents
-        .lines()
-        .filter(|l| l.trim() != line)
-        .collect();
-    let mut new_contents = filtered.join("\n");
-    if !new_contents.is_empty() {
-        new_contents.push('\n');
-    }
-    fs::write(&path, new_contents).map_err(|e| e.to_string())?;
-    Ok(())
-}
-
-fn rc_path(rc_name: &str) -> Result<PathBuf, String> {
-    let home = env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    Ok(Path::new(&home).join(rc_name))
-}
-
-fn current_session_name() -> Result<String, String> {
-    let out = Command::new("tmux")
-        .args(&["display-message", "-p", "#S"])
-        .output()
-        .map_err(|e| e.to_string())?;
-    if !out.status.success() {
-        return Err("tmux display-message failed".to_string());
-    }
-    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
-}
-
-fn percent_for_width(target_width: u16) -> String {
-    let pct = ((target_width as f32 / 120.0) * 100.0).clamp(10.0, 90.0);
-    format!("{:.0}", pct)
-}
-
-fn game_binary_path() -> Result<String, String> {
-    let exe = env::current_exe().map_err(|e| e.to_string())?;
-    let mut path = exe
-        .parent()
-        .map(PathBuf::from)
-        .ok_or_else(|| "unable to resolve current exe dir".to_string())?;
-    path.push("stack-game");
-    if path.exists() {
-        return path
-            .to_str()
-            .map(|s| s.to_string())
-            .ok_or_else(|| "non-utf8 path to stack-game".to_string());
+    match args.next().as_deref() {
+        None | Some("start") => run_launcher(&opts),
+        Some("stop") => stop_game(),
+        Some("status") => game_status(),
+        Some("toggle") => toggle_game(opts.split),
+        Some("mini") => publish_status_line(),
+        Some("guard") => guard_game(&opts),
+        Some("attach") => attach_game(),
+        Some("watch") => watch_game(&opts, args.next()),
+        Some("quit") => quit_session(),
+        Some("install-hook" | "install-hooks") => match args.next().as_deref() {
+            None => install_hook(None),
+            Some(name) => match Shell::parse(name) {
+                Some(shell) => install_hook(Some(shell)),
+                None => usage(),
+            },
+        },
+        Some("uninstall-hook" | "uninstall-hooks") => uninstall_hook(),
+        Some("stats") => show_stats(&opts, args.collect()),
+        _ => usage(),
     }
-    Ok("stack-game".to_string())
 }
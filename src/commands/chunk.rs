@@ -1,12 +1,14 @@
+use rand::Rng;
+
 use crate::{game::random_shape, CHUNK_SIZE};
 use crate::game::Piece;
 
 #[allow(dead_code)]
-pub fn command_to_pieces(cmd: &str) -> Vec<Piece> {
+pub fn command_to_pieces(cmd: &str, rng: &mut impl Rng) -> Vec<Piece> {
     let mut pieces = Vec::new();
     for chunk in command_to_chunks(cmd) {
         let payload = chunk_to_payload(&chunk);
-        pieces.push(Piece::with_payload(random_shape(), payload));
+        pieces.push(Piece::with_payload(random_shape(rng), payload));
     }
     pieces
 }
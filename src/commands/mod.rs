@@ -1,5 +1,5 @@
 mod chunk;
 mod tokenize;
 
-pub use chunk::{chunk_to_payload, command_to_chunks};
+pub use chunk::{chunk_to_payload, chunk_token, command_to_chunks};
 pub use tokenize::tokenize_command;
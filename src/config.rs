@@ -2,11 +2,22 @@
 pub const BOARD_W: usize = 10;
 pub const BOARD_H: usize = 20;
 pub const CELL_W: usize = 2; // render each block as two characters wide (letter + filler)
+pub const MAX_CELL_W: usize = 4; // widest cell the layout scales up to on roomy panes
 pub const PLAY_W: usize = BOARD_W * CELL_W + 2; // inner width plus side walls
 pub const PLAY_H: usize = BOARD_H + 2; // inner height plus ceiling/floor
 // Minimal pane width to fit the expanded info/controls width + cabinet border.
 pub const MIN_PANE_WIDTH: u16 = (PLAY_W as u16) + 2;
+pub const MIN_PANE_HEIGHT: u16 = (PLAY_H as u16) + 2;
 pub const CHUNK_SIZE: usize = 8;
-pub const SOCKET_PATH: &str = "/tmp/stack-game.sock";
+pub const REPEAT_SECS: u64 = 3; // after its first pass, a running command deals a chunk this often
+pub const RESULT_PIECES: usize = 3; // longest output snippet dropped when a command ends, in pieces
 pub const VARIETY_THRESH: i32 = 100;
 pub const BOMB_CAP: i32 = 3;
+pub const CRASH_GARBAGE: usize = 2; // garbage rows from a command that crashed (SIGSEGV and the like)
+pub const SPRINT_LINES: u64 = 40;
+pub const ULTRA_SECS: u64 = 180;
+pub const DANGER_ROWS: usize = 3; // warn once the stack is this close to the ceiling
+pub const LINES_PER_LEVEL: u64 = 10;
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const MAX_LONGEST_WAITS: usize = 10;
+pub const MAX_REPLAY_STEPS: usize = 100_000; // a game logging more than this isn't recorded
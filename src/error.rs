@@ -0,0 +1,44 @@
+// What went wrong, sorted by where it came from so a caller can pick its response: a usage
+// message for bad settings, a hint to start the game for a dead socket, and so on.
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    // Setting up, drawing to or restoring the terminal.
+    Terminal(io::Error),
+    // Talking to a game over its socket: nobody listening, a refusal, or a hang-up.
+    Socket(String),
+    // A flag, setting or file we were given that doesn't make sense.
+    Config(String),
+    // tmux, or screen in its place, failed or isn't there.
+    Tmux(String),
+}
+
+impl Error {
+    // The status to exit with: 2 for a usage problem, as for an unknown flag, else 1.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Config(_) => 2,
+            Error::Terminal(_) | Error::Socket(_) | Error::Tmux(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Terminal(e) => write!(f, "terminal: {e}"),
+            Error::Socket(msg) | Error::Config(msg) | Error::Tmux(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Terminal(e) => Some(e),
+            Error::Socket(_) | Error::Config(_) | Error::Tmux(_) => None,
+        }
+    }
+}
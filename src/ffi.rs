@@ -0,0 +1,116 @@
+// A C API over the game core, for tools that want the simulation without Rust: a vim plugin,
+// a status bar. include/waitris.h declares it. A game is an opaque handle from
+// `waitris_game_new`, freed with `waitris_game_free`; every call takes it first and does
+// nothing given NULL. Pointers other than the handle must be valid for the length given.
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{c_char, c_int, CStr};
+use std::time::Duration;
+
+use crate::game::{Action, Mode, Profile};
+use crate::io::parse_command_line;
+use crate::Game;
+
+// Board buffer values.
+const EMPTY: u8 = 0;
+const LOCKED: u8 = 1;
+const FALLING: u8 = 2;
+
+// A game already started in `mode` (0 classic, 1 zen, 2 sprint, 3 ultra; anything else is
+// classic) at normal speed.
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_new(seed: u64, mode: u32) -> Box<Game> {
+    let mode = Mode::ALL.get(mode as usize).copied().unwrap_or_default();
+    let mut game = Box::new(Game::with_seed(seed));
+    game.start(mode, Profile::Normal);
+    game
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_free(game: Option<Box<Game>>) {
+    drop(game);
+}
+
+// Feed one protocol line (`START`, `OUTPUT` or `END`, NUL-terminated).
+// 0 if it was applied, -1 if it didn't parse.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn waitris_game_event(game: Option<&mut Game>, line: *const c_char) -> c_int {
+    let Some(game) = game else { return -1 };
+    if line.is_null() {
+        return -1;
+    }
+    let line = unsafe { CStr::from_ptr(line) }.to_string_lossy();
+    match parse_command_line(&line) {
+        Some(ev) => {
+            game.handle_command_event(ev);
+            0
+        }
+        None => -1,
+    }
+}
+
+// Apply `action` (0 left, 1 right, 2 soft drop, 3 rotate, 4 hard drop, anything else none),
+// then let `elapsed_ms` of play time pass.
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_step(game: Option<&mut Game>, action: c_int, elapsed_ms: u64) {
+    let Some(game) = game else { return };
+    let action = match action {
+        0 => Some(Action::Left),
+        1 => Some(Action::Right),
+        2 => Some(Action::SoftDrop),
+        3 => Some(Action::Rotate),
+        4 => Some(Action::HardDrop),
+        _ => None,
+    };
+    game.step(action, Duration::from_millis(elapsed_ms));
+}
+
+// Fill `buf` with the board, row by row from the top: 0 empty, 1 locked, 2 the falling piece.
+// Returns the cells it takes, width times height; nothing is written if `len` is short.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn waitris_game_board(game: Option<&Game>, buf: *mut u8, len: usize) -> usize {
+    let Some(game) = game else { return 0 };
+    let (w, h) = (game.board.width, game.board.height);
+    if buf.is_null() || len < w * h {
+        return w * h;
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(buf, w * h) };
+    for y in 0..h {
+        for x in 0..w {
+            out[y * w + x] = if game.board.is_filled(x, y) { LOCKED } else { EMPTY };
+        }
+    }
+    if game.active_piece && !game.game_over {
+        for (x, y, _) in game.current.cells() {
+            if game.board.contains(x, y) {
+                out[y as usize * w + x as usize] = FALLING;
+            }
+        }
+    }
+    w * h
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_width(game: Option<&Game>) -> usize {
+    game.map_or(0, |game| game.board.width)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_height(game: Option<&Game>) -> usize {
+    game.map_or(0, |game| game.board.height)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_score(game: Option<&Game>) -> u64 {
+    game.map_or(0, |game| game.score)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_lines(game: Option<&Game>) -> u64 {
+    game.map_or(0, |game| game.lines_cleared)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn waitris_game_over(game: Option<&Game>) -> bool {
+    game.is_none_or(|game| game.game_over)
+}
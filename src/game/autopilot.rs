@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use crate::game::{evaluate, Action, Board, Cell, Piece, Placement};
+
+use super::placement::covered;
+use super::{Game, Step};
+
+// A landing spot for the current piece: how many quarter turns and which column.
+struct Target {
+    rotations: u8,
+    x: i32,
+    score: f64,
+}
+
+impl Game {
+    // Line the fresh piece up over the best landing spot once, then let gravity take it.
+    // Returns whether anything moved.
+    pub fn steer(&mut self) -> bool {
+        if self.steered || !self.active_piece || self.game_over {
+            return false;
+        }
+        self.record(Step::Steer);
+        self.steered = true;
+        let Some(target) = self.best_placement() else {
+            return false;
+        };
+        let mut moved = false;
+        for _ in 0..target.rotations {
+            moved |= self.rotate_current();
+        }
+        let step = (target.x - self.current.x).signum();
+        while self.current.x != target.x && self.move_current(step, 0) {
+            moved = true;
+        }
+        moved
+    }
+
+    fn best_placement(&self) -> Option<Target> {
+        let mut best: Option<Target> = None;
+        let mut turned = self.current.clone();
+        for rotations in 0..4 {
+            for x in -3..self.board.width as i32 {
+                let mut piece = turned.clone();
+                piece.x = x;
+                if !self.can_place(&piece) {
+                    continue;
+                }
+                while self.can_place(&piece.shifted(0, 1)) {
+                    piece = piece.shifted(0, 1);
+                }
+                let score = evaluate(&landed(&self.board, &piece));
+                if best.as_ref().is_none_or(|b| score > b.score) {
+                    best = Some(Target { rotations, x, score });
+                }
+            }
+            turned = turned.rotated();
+        }
+        best
+    }
+}
+
+impl Game {
+    // The reachable spot that leaves the best board by `evaluate`; the first of equals.
+    pub fn greedy_placement(&self) -> Option<Placement> {
+        let scored = self
+            .legal_placements()
+            .into_iter()
+            .map(|p| (evaluate(&p.landed(&self.board)), p));
+        scored.reduce(|best, next| if next.0 > best.0 { next } else { best }).map(|(_, p)| p)
+    }
+}
+
+// Plays by itself, one key per `pace` like a quick player: it picks the greedy spot for each
+// piece and heads for it, picking again if gravity or the player puts it out of reach. Its
+// keys go through `Game::step`, so a recording replays them like anyone else's.
+pub struct Autoplayer {
+    pace: Duration,
+    waited: Duration,
+    // Cells the current piece is headed for.
+    target: Option<Vec<(i32, i32)>>,
+}
+
+impl Autoplayer {
+    pub fn new(pace: Duration) -> Self {
+        Self {
+            pace,
+            waited: Duration::ZERO,
+            target: None,
+        }
+    }
+
+    pub fn pace(&self) -> Duration {
+        self.pace
+    }
+
+    // The key to press now, if one is due after `elapsed` more play time.
+    pub fn next_action(&mut self, game: &Game, elapsed: Duration) -> Option<Action> {
+        self.waited += elapsed;
+        if self.waited < self.pace {
+            return None;
+        }
+        self.waited = Duration::ZERO;
+        let on_course = self.target.as_ref().and_then(|target| {
+            game.legal_placements().into_iter().find(|p| covered(&p.piece) == *target)
+        });
+        let placement = on_course.or_else(|| game.greedy_placement())?;
+        self.target = Some(covered(&placement.piece));
+        placement.actions.first().copied()
+    }
+}
+
+fn landed(board: &Board, piece: &Piece) -> Board {
+    let mut board = board.clone();
+    for (x, y, _) in piece.cells() {
+        board.set(x as usize, y as usize, Cell::Filled(' ', ' ', piece.origin));
+    }
+    board
+}
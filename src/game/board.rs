@@ -1,10 +1,32 @@
-#[derive(Clone, Copy)]
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// Where a locked cell came from, so it can be traced back to its command.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Origin {
+    Command { run: u64, chunk: usize },
+    // A slice of the last line a command printed, dropped when it ended.
+    Result { run: u64 },
+    Filler,
+    Bomb,
+    Garbage,
+    Infected,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum Cell {
     Empty,
-    Filled(char, char),
+    Filled(char, char, Origin),
 }
 
-#[derive(Clone)]
+impl Cell {
+    pub fn is_filled(self) -> bool {
+        matches!(self, Cell::Filled(..))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Board {
     pub width: usize,
     pub height: usize,
@@ -32,4 +54,82 @@ impl Board {
         let idx = self.idx(x, y);
         self.cells[idx] = value;
     }
+
+    // Whether a piece cell at (x, y) would be on the board at all.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn is_filled(&self, x: usize, y: usize) -> bool {
+        self.get(x, y).is_filled()
+    }
+
+    pub fn row(&self, y: usize) -> &[Cell] {
+        &self.cells[self.idx(0, y)..self.idx(0, y + 1)]
+    }
+
+    pub fn is_row_full(&self, y: usize) -> bool {
+        self.row(y).iter().all(|c| c.is_filled())
+    }
+
+    // Rows with no gap left, top to bottom.
+    pub fn full_rows(&self) -> Vec<usize> {
+        (0..self.height).filter(|&y| self.is_row_full(y)).collect()
+    }
+
+    // Take out row `y`; everything above drops one row and an empty row comes in at the top.
+    pub fn clear_row(&mut self, y: usize) {
+        let start = self.idx(0, y);
+        self.cells.drain(start..start + self.width);
+        self.cells.splice(0..0, vec![Cell::Empty; self.width]);
+    }
+
+    // Every locked cell as (x, y, cell), row by row from the top.
+    pub fn iter_filled(&self) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_filled())
+            .map(|(i, &c)| (i % self.width, i / self.width, c))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter_filled().next().is_none()
+    }
+
+    // Height of column `x`'s top cell above the floor, holes underneath included.
+    pub fn column_height(&self, x: usize) -> usize {
+        (0..self.height)
+            .find(|&y| self.is_filled(x, y))
+            .map_or(0, |top| self.height - top)
+    }
+
+    // Column heights as one bar each, blank for an empty column up to '█' for a full one; the
+    // board at a glance where there's no room to draw it.
+    pub fn sparks(&self) -> String {
+        const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        (0..self.width)
+            .map(|x| BARS[(self.column_height(x) * 8).div_ceil(self.height.max(1))])
+            .collect()
+    }
+
+    // Height of the tallest column, counted up from the floor.
+    pub fn max_height(&self) -> usize {
+        (0..self.width).map(|x| self.column_height(x)).max().unwrap_or(0)
+    }
+}
+
+// One line per row, `#` for a locked cell and `.` for an empty one.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(y) {
+                f.write_str(if cell.is_filled() { "#" } else { "." })?;
+            }
+        }
+        Ok(())
+    }
 }
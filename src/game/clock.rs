@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::Game;
+
+// Wall time, for how long commands run and when recorded steps happened. Play time doesn't
+// come from here: `step` is told how much passed.
+pub trait Clock: Send {
+    // Time since some fixed point; only differences between readings are used.
+    fn now(&self) -> Duration;
+
+    // A copy of the game reads the same time.
+    fn boxed_clone(&self) -> Box<dyn Clock>;
+}
+
+// The OS monotonic clock. The default, except on wasm32, where std has no clock to read.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Default)]
+pub struct SystemClock;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        EPOCH.get_or_init(std::time::Instant::now).elapsed()
+    }
+
+    fn boxed_clone(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+// Only moves when told to, so a browser can feed it `performance.now()` and a test can run a
+// ten-minute command in no time. Clones share the reading.
+#[derive(Clone, Default)]
+pub struct ManualClock(Arc<Mutex<Duration>>);
+
+impl ManualClock {
+    pub fn set(&self, now: Duration) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn boxed_clone(&self) -> Box<dyn Clock> {
+        Box::new(self.clone())
+    }
+}
+
+pub(crate) struct WallClock(pub(crate) Box<dyn Clock>);
+
+impl WallClock {
+    pub(crate) fn now(&self) -> Duration {
+        self.0.now()
+    }
+}
+
+impl Default for WallClock {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default() -> Self {
+        Self(Box::new(SystemClock))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn default() -> Self {
+        Self(Box::new(ManualClock::default()))
+    }
+}
+
+impl Clone for WallClock {
+    fn clone(&self) -> Self {
+        Self(self.0.boxed_clone())
+    }
+}
+
+impl Game {
+    // Read wall time from `clock` from now on; commands already running were timed by the old
+    // one. The clock stays with the game through `restart` and `resume`.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.wall = WallClock(Box::new(clock));
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Board, Cell};
+
+// The cells that changed between two boards, so a copy elsewhere (another process, a spectator)
+// can follow a game without being sent the whole board each time. Usually a lock touches four
+// cells and a clear a few rows.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDiff {
+    // Size of the board this leads to; a copy of another size starts over from empty.
+    pub width: usize,
+    pub height: usize,
+    // (x, y, new value), row by row from the top.
+    pub changes: Vec<(usize, usize, Cell)>,
+}
+
+impl BoardDiff {
+    pub fn between(old: &Board, new: &Board) -> Self {
+        let same_size = old.width == new.width && old.height == new.height;
+        let changes = (0..new.height)
+            .flat_map(|y| (0..new.width).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                let cell = new.get(x, y);
+                let before = if same_size { old.get(x, y) } else { Cell::Empty };
+                (cell != before).then_some((x, y, cell))
+            })
+            .collect();
+        Self {
+            width: new.width,
+            height: new.height,
+            changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Bring `board` from the old state to the new one.
+    pub fn apply(&self, board: &mut Board) {
+        if board.width != self.width || board.height != self.height {
+            *board = Board::new(self.width, self.height);
+        }
+        for &(x, y, cell) in &self.changes {
+            if x < board.width && y < board.height {
+                board.set(x, y, cell);
+            }
+        }
+    }
+
+    // The rows anything changed in, top first and without repeats: what a redraw has to touch.
+    pub fn dirty_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.changes.iter().map(|&(_, y, _)| y).collect();
+        rows.dedup();
+        rows
+    }
+}
+
+impl Board {
+    // What changed going from `self` to `newer`.
+    pub fn diff(&self, newer: &Board) -> BoardDiff {
+        BoardDiff::between(self, newer)
+    }
+}
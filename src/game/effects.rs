@@ -1,10 +1,17 @@
 use rand::seq::IteratorRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::game::Cell;
-use crate::{BOMB_CAP, VARIETY_THRESH};
+use crate::game::{Cell, GameEvent, Origin};
 
-use super::Game;
+use super::{Game, Step};
+
+// The bomb-earning streak, kept between sessions so a relaunch doesn't break it.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct VarietyStreak {
+    pub last_identity: Option<String>,
+    pub streak: i32,
+}
 
 impl Game {
     pub(super) fn apply_bomb_clear(&mut self) {
@@ -12,13 +19,9 @@ impl Game {
         for (x, y, _) in self.current.cells() {
             for dy in -1..=1 {
                 for dx in -1..=1 {
-                    let nx = x + dx;
-                    let ny = y + dy;
-                    if nx >= 0 && ny >= 0 {
-                        let (xu, yu) = (nx as usize, ny as usize);
-                        if xu < self.board.width && yu < self.board.height {
-                            to_clear.push((xu, yu));
-                        }
+                    let (nx, ny) = (x + dx, y + dy);
+                    if self.board.contains(nx, ny) {
+                        to_clear.push((nx as usize, ny as usize));
                     }
                 }
             }
@@ -31,75 +34,78 @@ impl Game {
     }
 
     pub(super) fn apply_garbage_row(&mut self) {
-        let mut rng = rand::thread_rng();
-        let hole = rng.gen_range(0..self.board.width);
-        let mut new_cells = vec![Cell::Empty; self.board.width * self.board.height];
-        // shift everything up by one row
-        for y in 1..self.board.height {
-            for x in 0..self.board.width {
-                let src = self.board.get(x, y);
-                let dst_idx = (y - 1) * self.board.width + x;
-                new_cells[dst_idx] = src;
+        let hole = self.rng.gen_range(0..self.board.width);
+        // If top row had filled cells, game over.
+        let overflow = self.board.row(0).iter().any(|c| c.is_filled());
+        // Shift everything up by one row, then fill the bottom row except for the hole.
+        self.board.cells.drain(..self.board.width);
+        self.board.cells.extend((0..self.board.width).map(|x| {
+            if x == hole {
+                Cell::Empty
+            } else {
+                Cell::Filled('#', '░', Origin::Garbage)
             }
+        }));
+        // Rows waiting to clear and the falling piece ride up with the stack.
+        self.pending_clear.retain(|&y| y > 0);
+        for y in &mut self.pending_clear {
+            *y -= 1;
         }
-        // bottom row with garbage except hole
-        for x in 0..self.board.width {
-            let idx = (self.board.height - 1) * self.board.width + x;
-            if x == hole {
-                new_cells[idx] = Cell::Empty;
+        if self.active_piece && !self.can_place(&self.current) {
+            let lifted = self.current.shifted(0, -1);
+            if self.can_place(&lifted) {
+                self.current = lifted;
             } else {
-                new_cells[idx] = Cell::Filled('#', '░');
+                self.finish();
             }
         }
-        // If top row had filled cells, game over.
-        let overflow = (0..self.board.width).any(|x| matches!(self.board.get(x, 0), Cell::Filled(_, _)));
-        self.board.cells = new_cells;
         if overflow {
-            self.game_over = true;
+            self.finish();
         }
     }
 
     pub(super) fn apply_infection(&mut self) {
-        let mut rng = rand::thread_rng();
-        let mut filled: Vec<(usize, usize)> = Vec::new();
-        for y in 0..self.board.height {
-            for x in 0..self.board.width {
-                if let Cell::Filled(_, _) = self.board.get(x, y) {
-                    filled.push((x, y));
-                }
-            }
-        }
+        let filled: Vec<(usize, usize)> = self.board.iter_filled().map(|(x, y, _)| (x, y)).collect();
         let count = filled.len().min(5);
-        for &(x, y) in filled.iter().choose_multiple(&mut rng, count) {
-            self.board.set(x, y, Cell::Filled('?', '░'));
+        for &(x, y) in filled.iter().choose_multiple(&mut self.rng, count) {
+            self.board.set(x, y, Cell::Filled('?', '░', Origin::Infected));
         }
     }
 
+    pub fn variety(&self) -> VarietyStreak {
+        VarietyStreak {
+            last_identity: self.last_cmd_identity.clone(),
+            streak: self.variety_streak,
+        }
+    }
+
+    pub fn carry_variety(&mut self, carried: VarietyStreak) {
+        self.record(Step::Variety(carried.clone()));
+        self.last_cmd_identity = carried.last_identity;
+        self.variety_streak = carried.streak;
+    }
+
     pub(super) fn apply_variety(&mut self, identity: &str, exit_code: i32) {
         let same_as_last = self.last_cmd_identity.as_deref() == Some(identity);
+        self.variety_meter = (self.variety_meter - self.rules.0.variety_decay(same_as_last)).max(0);
         if same_as_last {
-            self.variety_meter = (self.variety_meter - 5).max(0);
             self.variety_streak = 0;
         } else {
-            self.variety_meter = (self.variety_meter - 2).max(0);
             self.variety_streak += 1;
         }
+        self.variety_meter +=
+            self.rules.0.variety_points(same_as_last, self.variety_streak, exit_code != 0);
 
-        let mut variety_points = if same_as_last {
-            0
-        } else {
-            10 + 3 * (self.variety_streak.min(10))
-        };
-
-        if exit_code != 0 {
-            variety_points /= 2;
-        }
-
-        self.variety_meter += variety_points;
-
-        while self.variety_meter >= VARIETY_THRESH {
-            self.variety_meter -= VARIETY_THRESH;
-            self.bombs = (self.bombs + 1).min(BOMB_CAP);
+        // A threshold of zero would never drain the meter.
+        let threshold = self.bomb_threshold().max(1);
+        while self.variety_meter >= threshold {
+            self.variety_meter -= threshold;
+            if self.bombs < self.bomb_cap() {
+                self.bombs += 1;
+                self.stats.bombs_earned += 1;
+                self.stats.credit(identity).bombs += 1;
+                self.emit(GameEvent::BombEarned { bombs: self.bombs });
+            }
         }
     }
 }
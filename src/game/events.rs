@@ -0,0 +1,44 @@
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+
+use super::Game;
+
+// What happened in a game, for anything that wants to react to it (UI, stats, sound,
+// notifications) without polling the game's fields frame by frame.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    // `run` is the command the piece was cut from; bombs and filler pieces have none.
+    PieceLocked { run: Option<u64>, bomb: bool },
+    // Rows collapsed once their flash finished; `combo` counts the clearing locks in a row.
+    LinesCleared { count: u32, combo: u32 },
+    // The variety meter filled; `bombs` is how many are now in hand.
+    BombEarned { bombs: i32 },
+    // A failing command pushed a garbage row up from the bottom.
+    GarbageAdded { run: u64 },
+    GameOver { score: u64, lines: u64, goal_reached: bool },
+}
+
+// A copy of a game plays on its own, so it starts with nobody listening.
+#[derive(Default)]
+pub(crate) struct Subscribers(Vec<mpsc::Sender<GameEvent>>);
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Game {
+    // Every event from now on, this game and the ones `restart` and `resume` turn it into.
+    // Dropping the receiver unsubscribes.
+    pub fn subscribe(&mut self) -> mpsc::Receiver<GameEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.0.push(tx);
+        rx
+    }
+
+    pub(super) fn emit(&mut self, event: GameEvent) {
+        self.subscribers.0.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
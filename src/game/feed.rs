@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use crate::REPEAT_SECS;
+
+use super::Game;
+
+// How a command that's still running keeps the queue fed once its chunks have all been dealt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feed {
+    // Its chunks come round again, one per `every` of play. The default, every REPEAT_SECS.
+    Repeat { every: Duration },
+    // A plain filler piece, the command's heartbeat, every `every` of play until it ends. Its
+    // chunks are dealt once, so a long build doesn't fill the board with the same text.
+    Trickle { every: Duration },
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Feed::Repeat {
+            every: Duration::from_secs(REPEAT_SECS),
+        }
+    }
+}
+
+impl Feed {
+    // Play time between one piece and the next. Never zero, which would deal without end.
+    pub fn every(self) -> Duration {
+        let (Feed::Repeat { every } | Feed::Trickle { every }) = self;
+        every.max(Duration::from_millis(1))
+    }
+}
+
+impl Game {
+    // Feed running commands this way from now on. Like the piece source, it stays with the
+    // game through `restart` and `resume`.
+    pub fn set_feed(&mut self, feed: Feed) {
+        self.feed = feed;
+    }
+
+    pub fn feed(&self) -> Feed {
+        self.feed
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::CHUNK_SIZE;
+
+use super::Game;
+
+impl Game {
+    // Panic if the game is in a state no sequence of events should reach. Debug builds run
+    // this after every step, input and command event; call it yourself after editing fields
+    // by hand.
+    pub fn assert_invariants(&self) {
+        let board = &self.board;
+        assert_eq!(board.cells.len(), board.width * board.height, "board size");
+
+        // The board is the whole well, so a falling piece off it or inside the stack is
+        // already a top-out.
+        if self.active_piece && !self.game_over {
+            assert!(
+                self.can_place(&self.current),
+                "falling piece overlaps the stack or leaves the board\n{board}"
+            );
+        }
+        assert_eq!(self.current.payload.len(), CHUNK_SIZE, "falling piece payload");
+
+        // Repeat cycles of a run are queued in order, and never ahead of the run itself.
+        let mut last_cycle = HashMap::new();
+        for queued in &self.piece_queue {
+            assert_eq!(queued.piece.payload.len(), CHUNK_SIZE, "queued piece payload");
+            if queued.is_bomb {
+                continue;
+            }
+            let last = last_cycle.entry(queued.run_id).or_insert(queued.cycle);
+            assert!(queued.cycle >= *last, "run {} queued out of cycle order", queued.run_id);
+            *last = queued.cycle;
+            if let Some(run) = self.active_runs.get(&queued.run_id) {
+                assert!(queued.cycle <= run.cycle, "run {} queued ahead of itself", run.id);
+            }
+        }
+
+        assert!(
+            self.pending_clear.iter().all(|&y| y < board.height && board.is_row_full(y)),
+            "clearing a row that isn't full"
+        );
+        assert!(self.bombs >= 0, "negative bombs");
+    }
+
+    // After every outside mutation in debug builds; free in release.
+    pub(super) fn debug_assert_invariants(&self) {
+        if cfg!(debug_assertions) {
+            self.assert_invariants();
+        }
+    }
+}
@@ -1,8 +1,35 @@
+pub mod autopilot;
 pub mod board;
+pub mod clock;
+pub mod diff;
 pub mod effects;
+pub mod events;
+pub mod feed;
+mod invariants;
+pub mod mode;
 pub mod piece;
+pub mod placement;
+pub mod recording;
+pub mod scoring;
+pub mod source;
 pub mod state;
+pub mod stats;
+mod timestep;
 
-pub use board::{Board, Cell};
-pub use piece::{random_shape, Piece, Shape};
-pub use state::{CommandEvent, Game};
+pub use autopilot::Autoplayer;
+pub use board::{Board, Cell, Origin};
+#[cfg(not(target_arch = "wasm32"))]
+pub use clock::SystemClock;
+pub use clock::{Clock, ManualClock};
+pub use diff::BoardDiff;
+pub use effects::VarietyStreak;
+pub use events::GameEvent;
+pub use feed::Feed;
+pub use mode::{Mode, Profile};
+pub use piece::{random_shape, KickTable, Piece, RotationSystem, Shape};
+pub use placement::{evaluate, Placement};
+pub use recording::{Recording, Step};
+pub use scoring::{Guideline, ScoringRules};
+pub use source::{ChunkShapes, PieceSource, RandomSource, Scripted, SevenBag};
+pub use state::{Action, CommandEvent, Exit, Game};
+pub use stats::{CommandLog, Contribution, CurrentGame, RunStats, SessionClock, SessionSummary, SessionTotals};
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SPRINT_LINES, ULTRA_SECS};
+
+use super::RotationSystem;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Mode {
+    // Pieces only come from shell commands; a failing command adds garbage.
+    #[default]
+    Classic,
+    // Command-fed, but failures are forgiven and topping out just wipes the board.
+    Zen,
+    // Clear SPRINT_LINES as fast as possible with a steady supply of filler pieces.
+    Sprint,
+    // Score as much as possible in ULTRA_SECS with a steady supply of filler pieces.
+    Ultra,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [Mode::Classic, Mode::Zen, Mode::Sprint, Mode::Ultra];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Classic => "CLASSIC",
+            Mode::Zen => "ZEN",
+            Mode::Sprint => "SPRINT",
+            Mode::Ultra => "ULTRA",
+        }
+    }
+
+    pub fn blurb(self) -> String {
+        match self {
+            Mode::Classic => "pieces come from your commands".to_string(),
+            Mode::Zen => "no garbage, no game over".to_string(),
+            Mode::Sprint => format!("clear {SPRINT_LINES} lines fast"),
+            Mode::Ultra => format!("best score in {}m", ULTRA_SECS / 60),
+        }
+    }
+
+    // Sprint/Ultra keep the well stocked even when no command is running.
+    pub fn self_feeding(self) -> bool {
+        matches!(self, Mode::Sprint | Mode::Ultra)
+    }
+
+    pub fn punishes_failures(self) -> bool {
+        !matches!(self, Mode::Zen)
+    }
+
+    // The race modes kick like guideline games; command-fed play keeps blocked turns blocked.
+    pub fn rotation_system(self) -> RotationSystem {
+        match self {
+            Mode::Classic | Mode::Zen => RotationSystem::None,
+            Mode::Sprint | Mode::Ultra => RotationSystem::Srs,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Profile {
+    Casual,
+    #[default]
+    Normal,
+    Hardcore,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 3] = [Profile::Casual, Profile::Normal, Profile::Hardcore];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Casual => "CASUAL",
+            Profile::Normal => "NORMAL",
+            Profile::Hardcore => "HARDCORE",
+        }
+    }
+
+    // As given to --profile, in any case.
+    pub fn parse(s: &str) -> Option<Self> {
+        Profile::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(s))
+    }
+
+    pub fn gravity_interval(self) -> Duration {
+        match self {
+            Profile::Casual => Duration::from_millis(700),
+            Profile::Normal => Duration::from_millis(450),
+            Profile::Hardcore => Duration::from_millis(250),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy)]
+use super::Origin;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Shape {
     I,
     O,
@@ -12,13 +15,92 @@ pub enum Shape {
     L,
 }
 
-#[derive(Clone)]
+impl Shape {
+    pub const ALL: [Shape; 7] = [
+        Shape::I,
+        Shape::O,
+        Shape::T,
+        Shape::S,
+        Shape::Z,
+        Shape::J,
+        Shape::L,
+    ];
+
+    // Offsets to try, in order, when turning clockwise out of `rotation` doesn't fit where the
+    // piece is. Board coordinates: y grows downward.
+    pub fn kicks(self, rotation: u8, system: RotationSystem) -> &'static [(i32, i32)] {
+        let r = (rotation % 4) as usize;
+        match (system, self) {
+            (RotationSystem::None, _) | (_, Shape::O) => &[],
+            (RotationSystem::Srs, Shape::I) => &SRS_I[r],
+            (RotationSystem::Srs, _) => &SRS_JLSTZ[r],
+            // Arika's rule: one step right, then one step left; the long bar never kicks.
+            (RotationSystem::Ars, Shape::I) => &[],
+            (RotationSystem::Ars, _) => &[(1, 0), (-1, 0)],
+        }
+    }
+}
+
+// The guideline tables, with y flipped to point down.
+const SRS_JLSTZ: [[(i32, i32); 4]; 4] = [
+    [(-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(1, 0), (1, 1), (0, -2), (1, -2)],
+    [(1, 0), (1, -1), (0, 2), (1, 2)],
+    [(-1, 0), (-1, 1), (0, -2), (-1, -2)],
+];
+const SRS_I: [[(i32, i32); 4]; 4] = [
+    [(-2, 0), (1, 0), (-2, 1), (1, -2)],
+    [(-1, 0), (2, 0), (-1, -2), (2, 1)],
+    [(2, 0), (-1, 0), (2, -1), (-1, 2)],
+    [(1, 0), (-2, 0), (1, 2), (-2, -1)],
+];
+
+// How a rotation that doesn't fit in place gets nudged into one that does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum RotationSystem {
+    // Blocked means blocked.
+    #[default]
+    None,
+    // Guideline Super Rotation System.
+    Srs,
+    // Arika-style: a single sideways nudge either way.
+    Ars,
+}
+
+// Kicks for every shape and starting rotation, for rules that don't match a built-in system.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct KickTable {
+    kicks: [[Vec<(i32, i32)>; 4]; 7],
+}
+
+impl KickTable {
+    pub fn from_system(system: RotationSystem) -> Self {
+        let mut table = Self::default();
+        for shape in Shape::ALL {
+            for rotation in 0..4 {
+                table.set(shape, rotation, shape.kicks(rotation, system).to_vec());
+            }
+        }
+        table
+    }
+
+    pub fn set(&mut self, shape: Shape, rotation: u8, offsets: Vec<(i32, i32)>) {
+        self.kicks[shape as usize][(rotation % 4) as usize] = offsets;
+    }
+
+    pub fn get(&self, shape: Shape, rotation: u8) -> &[(i32, i32)] {
+        &self.kicks[shape as usize][(rotation % 4) as usize]
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Piece {
     pub shape: Shape,
     pub rotation: u8,
     pub x: i32,
     pub y: i32,
     pub payload: Vec<char>,
+    pub origin: Origin,
 }
 
 impl Piece {
@@ -29,9 +111,15 @@ impl Piece {
             x: 3,
             y: 0,
             payload,
+            origin: Origin::Filler,
         }
     }
 
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
     pub fn cells(&self) -> Vec<(i32, i32, char)> {
         let offsets = shape_offsets(self.shape, self.rotation);
         offsets
@@ -77,18 +165,8 @@ impl Piece {
     }
 }
 
-pub fn random_shape() -> Shape {
-    let shapes = [
-        Shape::I,
-        Shape::O,
-        Shape::T,
-        Shape::S,
-        Shape::Z,
-        Shape::J,
-        Shape::L,
-    ];
-    let mut rng = thread_rng();
-    *shapes.choose(&mut rng).unwrap_or(&Shape::I)
+pub fn random_shape(rng: &mut impl Rng) -> Shape {
+    *Shape::ALL.choose(rng).unwrap_or(&Shape::I)
 }
 
 pub fn shape_offsets(shape: Shape, rotation: u8) -> &'static [(i32, i32)] {
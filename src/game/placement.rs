@@ -0,0 +1,110 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::game::{Action, Board, Cell, Piece};
+
+use super::Game;
+
+// A spot the falling piece can come to rest in, and the keys that put it there.
+#[derive(Clone)]
+pub struct Placement {
+    // The piece as it would lock.
+    pub piece: Piece,
+    // From where the piece is now; always ends in the hard drop that locks it.
+    pub actions: Vec<Action>,
+}
+
+impl Placement {
+    // The board once the piece has locked, before any full rows clear.
+    pub fn landed(&self, board: &Board) -> Board {
+        let mut board = board.clone();
+        for (x, y, _) in self.piece.cells() {
+            if board.contains(x, y) {
+                board.set(x as usize, y as usize, Cell::Filled(' ', ' ', self.piece.origin));
+            }
+        }
+        board
+    }
+}
+
+impl Game {
+    // Every resting spot the falling piece can reach with left, right, rotate and soft drop,
+    // tucks and kicks included, by the fewest keys. Spots covering the same cells count once.
+    // Empty with no piece falling or after game over.
+    pub fn legal_placements(&self) -> Vec<Placement> {
+        if !self.active_piece || self.game_over {
+            return Vec::new();
+        }
+        let key = |piece: &Piece| (piece.x, piece.y, piece.rotation);
+        let mut seen = HashSet::from([key(&self.current)]);
+        let mut frontier = VecDeque::from([(self.current.clone(), Vec::new())]);
+        let mut seen_spots = HashSet::new();
+        let mut spots = Vec::new();
+        while let Some((piece, actions)) = frontier.pop_front() {
+            // Turns and slides before drops, so of equally short paths the one that lines up
+            // first and falls last wins: its drops fold into the final hard drop.
+            if let Some(next) = self.turn(&piece) {
+                push(&mut frontier, &mut seen, key(&next), next, &actions, Action::Rotate);
+            }
+            for (dx, action) in [(-1, Action::Left), (1, Action::Right)] {
+                let next = piece.shifted(dx, 0);
+                if self.can_place(&next) {
+                    push(&mut frontier, &mut seen, key(&next), next, &actions, action);
+                }
+            }
+            let down = piece.shifted(0, 1);
+            if self.can_place(&down) {
+                push(&mut frontier, &mut seen, key(&down), down, &actions, Action::SoftDrop);
+            } else if seen_spots.insert(covered(&piece)) {
+                spots.push(Placement { piece: piece.clone(), actions: locking(&actions) });
+            }
+        }
+        spots
+    }
+}
+
+fn push(
+    frontier: &mut VecDeque<(Piece, Vec<Action>)>,
+    seen: &mut HashSet<(i32, i32, u8)>,
+    key: (i32, i32, u8),
+    piece: Piece,
+    actions: &[Action],
+    action: Action,
+) {
+    if seen.insert(key) {
+        let mut actions = actions.to_vec();
+        actions.push(action);
+        frontier.push_back((piece, actions));
+    }
+}
+
+// The cells a piece sits on, sorted, so two ways of filling the same spot compare equal.
+pub(super) fn covered(piece: &Piece) -> Vec<(i32, i32)> {
+    let mut cells: Vec<(i32, i32)> = piece.cells().into_iter().map(|(x, y, _)| (x, y)).collect();
+    cells.sort_unstable();
+    cells
+}
+
+// A hard drop covers whatever soft drops the path ended with.
+fn locking(actions: &[Action]) -> Vec<Action> {
+    let keep = actions.iter().rposition(|&a| a != Action::SoftDrop).map_or(0, |i| i + 1);
+    let mut actions = actions[..keep].to_vec();
+    actions.push(Action::HardDrop);
+    actions
+}
+
+// How good a board is to play on: rows it would clear, less aggregate column height, holes
+// (empty cells under a column's top) and bumpiness (height steps between neighbours). Higher
+// is better; the weights are the classic hand-tuned ones, good enough to stay alive.
+pub fn evaluate(board: &Board) -> f64 {
+    let lines = board.full_rows().len();
+    let heights: Vec<usize> = (0..board.width).map(|x| board.column_height(x)).collect();
+    let holes: usize = (0..board.width)
+        .map(|x| {
+            let top = board.height - heights[x];
+            (top..board.height).filter(|&y| !board.is_filled(x, y)).count()
+        })
+        .sum();
+    let aggregate: usize = heights.iter().sum();
+    let bumpiness: usize = heights.windows(2).map(|w| w[0].abs_diff(w[1])).sum();
+    0.76 * lines as f64 - 0.51 * aggregate as f64 - 0.36 * holes as f64 - 0.18 * bumpiness as f64
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::MAX_REPLAY_STEPS;
+
+use super::{CommandEvent, Action, Game, Mode, Profile, VarietyStreak};
+
+// Everything that moves a game forward from outside. Replayed in order against a game built
+// from the same seed, these reproduce it move for move.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Step {
+    Start { mode: Mode, profile: Profile },
+    Resume(Box<Game>),
+    // Only in replays from before each game was recorded on its own.
+    Restart,
+    // The first step of a restarted game's replay: the game as the restart left it, with
+    // whatever commands were still running, just before it begins.
+    Restarted(Box<Game>),
+    // A streak carried over from the last session.
+    Variety(VarietyStreak),
+    Command(CommandEvent),
+    Input(Action),
+    Steer,
+    Gravity,
+    // Only frames that had an animation to advance; the rest change nothing.
+    Effects,
+    // Play time that passed since the previous step; Ultra's clock runs on it.
+    Clock(Duration),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimedStep {
+    // Milliseconds since recording started, for paced playback.
+    pub at_ms: u64,
+    pub step: Step,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub seed: u64,
+    pub steps: Vec<TimedStep>,
+    // The game clock's reading when recording began.
+    #[serde(skip)]
+    started: Duration,
+}
+
+impl Game {
+    // Keep a log of every step from here on; replay needs it to start with the game. A
+    // restart starts the log afresh for the next game.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording {
+            seed: self.seed,
+            steps: Vec::new(),
+            started: self.wall.now(),
+        });
+    }
+
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    // Play time is logged lazily, just ahead of the next step that could read it, so a quiet
+    // stretch of frames costs one entry instead of one per frame. A game that runs past
+    // MAX_REPLAY_STEPS stops recording rather than grow without end.
+    pub(super) fn record(&mut self, step: Step) {
+        if self.recording.as_ref().is_some_and(|r| r.steps.len() >= MAX_REPLAY_STEPS) {
+            log::warn!("game passed {MAX_REPLAY_STEPS} steps; no replay for it");
+            self.recording = None;
+        }
+        if let Some(recording) = &mut self.recording {
+            let at_ms = self.wall.now().saturating_sub(recording.started).as_millis() as u64;
+            let unlogged = std::mem::take(&mut self.unlogged_clock);
+            if !unlogged.is_zero() {
+                recording.steps.push(TimedStep {
+                    at_ms,
+                    step: Step::Clock(unlogged),
+                });
+            }
+            recording.steps.push(TimedStep { at_ms, step });
+        }
+    }
+
+    pub fn apply(&mut self, step: Step) {
+        match step {
+            Step::Start { mode, profile } => self.start(mode, profile),
+            Step::Resume(saved) => self.resume(*saved),
+            Step::Restart => self.restart(),
+            Step::Restarted(game) => {
+                self.take_over(*game);
+                self.begin(self.mode, self.profile);
+            }
+            Step::Variety(carried) => self.carry_variety(carried),
+            Step::Command(ev) => self.handle_command_event(ev),
+            Step::Input(input) => self.input(input),
+            Step::Steer => {
+                self.steer();
+            }
+            Step::Gravity => self.tick_gravity(),
+            Step::Effects => self.process_effects(),
+            Step::Clock(elapsed) => self.advance_clock(elapsed),
+        }
+    }
+}
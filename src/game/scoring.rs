@@ -0,0 +1,98 @@
+use crate::{BOMB_CAP, VARIETY_THRESH};
+
+use super::Game;
+
+// The numbers behind the score and the bomb supply. Swap a ruleset in with
+// `Game::set_scoring_rules` to change them without touching the game logic.
+pub trait ScoringRules: Send {
+    // Points for `lines` rows collapsing at once, at `level`, `combo` clearing locks into a
+    // streak (1 for the first).
+    fn clear_points(&self, lines: u32, level: u64, combo: u32) -> u64;
+
+    // What a finished command drains from the variety meter before adding anything.
+    fn variety_decay(&self, same_as_last: bool) -> i32;
+
+    // What a finished command adds to the variety meter; `streak` counts different commands
+    // in a row, this one included.
+    fn variety_points(&self, same_as_last: bool, streak: i32, failed: bool) -> i32;
+
+    // Meter points per bomb.
+    fn bomb_threshold(&self) -> i32;
+
+    // Most bombs in hand at once.
+    fn bomb_cap(&self) -> i32;
+
+    // A copy of the game scores the same way.
+    fn boxed_clone(&self) -> Box<dyn ScoringRules>;
+}
+
+// 100/300/500/800 for one to four lines whatever the level, and the variety meter as the help
+// screen describes it. The default.
+#[derive(Clone, Default)]
+pub struct Guideline;
+
+impl ScoringRules for Guideline {
+    fn clear_points(&self, lines: u32, _level: u64, _combo: u32) -> u64 {
+        match lines {
+            1 => 100,
+            2 => 300,
+            3 => 500,
+            4 => 800,
+            _ => 0,
+        }
+    }
+
+    fn variety_decay(&self, same_as_last: bool) -> i32 {
+        if same_as_last { 5 } else { 2 }
+    }
+
+    fn variety_points(&self, same_as_last: bool, streak: i32, failed: bool) -> i32 {
+        if same_as_last {
+            return 0;
+        }
+        let points = 10 + 3 * streak.min(10);
+        if failed { points / 2 } else { points }
+    }
+
+    fn bomb_threshold(&self) -> i32 {
+        VARIETY_THRESH
+    }
+
+    fn bomb_cap(&self) -> i32 {
+        BOMB_CAP
+    }
+
+    fn boxed_clone(&self) -> Box<dyn ScoringRules> {
+        Box::new(self.clone())
+    }
+}
+
+pub(crate) struct Rules(pub(crate) Box<dyn ScoringRules>);
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self(Box::new(Guideline))
+    }
+}
+
+impl Clone for Rules {
+    fn clone(&self) -> Self {
+        Self(self.0.boxed_clone())
+    }
+}
+
+impl Game {
+    // Score by `rules` from now on. Like the piece source, they stay with the game through
+    // `restart` and `resume`.
+    pub fn set_scoring_rules(&mut self, rules: impl ScoringRules + 'static) {
+        self.rules = Rules(Box::new(rules));
+    }
+
+    pub fn bomb_threshold(&self) -> i32 {
+        self.rules.0.bomb_threshold()
+    }
+
+    pub fn bomb_cap(&self) -> i32 {
+        self.rules.0.bomb_cap()
+    }
+}
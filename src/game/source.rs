@@ -0,0 +1,132 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use super::{random_shape, Game, Shape};
+
+// Picks the shape of every piece a game deals: command chunks, repeat cycles and filler alike.
+// Any randomness should come from the game's `rng`, so a seed still pins down the whole game.
+pub trait PieceSource: Send {
+    // `payload` is the text the piece will carry; filler is all '░'.
+    fn next_shape(&mut self, rng: &mut StdRng, payload: &[char]) -> Shape;
+
+    // A copy of the game deals on from the same point.
+    fn boxed_clone(&self) -> Box<dyn PieceSource>;
+
+    // A restart begins a game of its own seed; drop anything dealt from the last one.
+    fn new_game(&mut self) {}
+}
+
+// Each shape equally likely, every time. The default.
+#[derive(Clone, Default)]
+pub struct RandomSource;
+
+impl PieceSource for RandomSource {
+    fn next_shape(&mut self, rng: &mut StdRng, _payload: &[char]) -> Shape {
+        random_shape(rng)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn PieceSource> {
+        Box::new(self.clone())
+    }
+}
+
+// All seven shapes in a shuffled bag, dealt out before the next bag is shuffled, so no shape
+// is ever more than twelve pieces away.
+#[derive(Clone, Default)]
+pub struct SevenBag {
+    bag: Vec<Shape>,
+}
+
+impl PieceSource for SevenBag {
+    fn next_shape(&mut self, rng: &mut StdRng, _payload: &[char]) -> Shape {
+        if self.bag.is_empty() {
+            self.bag = Shape::ALL.to_vec();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap_or(Shape::I)
+    }
+
+    fn new_game(&mut self) {
+        self.bag.clear();
+    }
+
+    fn boxed_clone(&self) -> Box<dyn PieceSource> {
+        Box::new(self.clone())
+    }
+}
+
+// The given shapes in order, over and over, from the top for each new game; for tests and puzzles.
+#[derive(Clone)]
+pub struct Scripted {
+    shapes: Vec<Shape>,
+    next: usize,
+}
+
+impl Scripted {
+    pub fn new(shapes: Vec<Shape>) -> Self {
+        Self { shapes, next: 0 }
+    }
+}
+
+impl PieceSource for Scripted {
+    fn next_shape(&mut self, _rng: &mut StdRng, _payload: &[char]) -> Shape {
+        if self.shapes.is_empty() {
+            return Shape::I;
+        }
+        let shape = self.shapes[self.next % self.shapes.len()];
+        self.next += 1;
+        shape
+    }
+
+    fn new_game(&mut self) {
+        self.next = 0;
+    }
+
+    fn boxed_clone(&self) -> Box<dyn PieceSource> {
+        Box::new(self.clone())
+    }
+}
+
+// The shape follows from the text the piece carries, so a command always stacks as the same
+// pieces. Filler, which carries nothing, is random.
+#[derive(Clone, Default)]
+pub struct ChunkShapes;
+
+impl PieceSource for ChunkShapes {
+    fn next_shape(&mut self, rng: &mut StdRng, payload: &[char]) -> Shape {
+        if payload.iter().all(|&ch| ch == '░') {
+            return random_shape(rng);
+        }
+        // FNV-1a: stable across builds, unlike the std hasher.
+        let hash = payload.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &ch| {
+            (hash ^ u64::from(ch)).wrapping_mul(0x0100_0000_01b3)
+        });
+        Shape::ALL[(hash % Shape::ALL.len() as u64) as usize]
+    }
+
+    fn boxed_clone(&self) -> Box<dyn PieceSource> {
+        Box::new(self.clone())
+    }
+}
+
+pub(crate) struct Source(pub(crate) Box<dyn PieceSource>);
+
+impl Default for Source {
+    fn default() -> Self {
+        Self(Box::new(RandomSource))
+    }
+}
+
+impl Clone for Source {
+    fn clone(&self) -> Self {
+        Self(self.0.boxed_clone())
+    }
+}
+
+impl Game {
+    // Deal from `source` from the next piece on; pieces already queued keep their shapes. The
+    // source stays with the game through `restart` and `resume`.
+    pub fn set_piece_source(&mut self, source: impl PieceSource + 'static) {
+        self.source = Source(Box::new(source));
+    }
+}
@@ -1,16 +1,78 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::commands;
-use crate::game::{random_shape, Board, Cell, Piece, Shape};
-use crate::{BOARD_H, BOARD_W, CHUNK_SIZE};
+use crate::game::clock::WallClock;
+use crate::game::events::Subscribers;
+use crate::game::scoring::Rules;
+use crate::game::source::{PieceSource, Source};
+use crate::game::timestep::FixedStep;
+use crate::game::{
+    Board, Cell, Feed, GameEvent, KickTable, Mode, Origin, Piece, Profile, Recording, RunStats,
+    Shape, Step,
+};
+use crate::{
+    BOARD_H, BOARD_W, CHUNK_SIZE, CRASH_GARBAGE, DANGER_ROWS, LINES_PER_LEVEL, RESULT_PIECES,
+    SPRINT_LINES, ULTRA_SECS,
+};
 
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CommandEvent {
     Start { id: u64, command: String },
-    End { id: u64, _exit_code: i32 },
+    End {
+        id: u64,
+        #[serde(rename = "exit_code")]
+        _exit_code: i32,
+    },
+    // A line the command printed, sent before its END; the last one becomes result pieces.
+    Output { id: u64, line: String },
+}
+
+// How a command ended, read off its exit code the way shells report it: 128+N when signal N
+// killed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Success,
+    Failed,
+    // Ctrl-C (SIGINT): the player called it off, so it isn't held against them.
+    Interrupted,
+    // SIGILL, SIGABRT, SIGBUS, SIGFPE or SIGSEGV: worse than failing.
+    Crashed,
+}
+
+impl Exit {
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => Exit::Success,
+            130 => Exit::Interrupted,
+            132 | 134 | 135 | 136 | 139 => Exit::Crashed,
+            _ => Exit::Failed,
+        }
+    }
 }
 
+// Player moves, from whatever frontend produced them: keys, mouse, a script or a replay. Held
+// back while cleared rows flash and replayed once they collapse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Left,
+    Right,
+    SoftDrop,
+    Rotate,
+    HardDrop,
+}
+
+const MAX_HELD_INPUTS: usize = 8;
+
+// Flashes and fades advance one frame per this much play time.
+const EFFECT_FRAME: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct QueuedPiece {
     pub run_id: u64,
     pub cycle: u64,
@@ -18,37 +80,120 @@ pub(crate) struct QueuedPiece {
     pub is_bomb: bool,
 }
 
+#[derive(Clone)]
 pub(crate) struct CommandRun {
     pub id: u64,
     pub chunks: Vec<String>,
     pub cycle: u64,
     pub active: bool,
     pub identity: String,
+    pub command: String,
+    // Wall-clock readings from the game's `Clock`.
+    pub started_at: Duration,
+    pub ended_at: Option<Duration>,
+    pub pieces_dealt: u32,
+    // Where the game stood when the command started, for the longest-wait recap.
+    pub lines_at_start: u64,
+    pub score_at_start: u64,
+    // The last line of output reported for it, if the hook sent any.
+    pub output: Option<String>,
+    // Chunks dealt again after the first pass, one at a time, and the play-clock reading at
+    // which the next one is due.
+    pub repeats: usize,
+    pub next_repeat: Duration,
 }
 
 impl CommandRun {
-    fn new(id: u64, chunks: Vec<String>, identity: String) -> Self {
+    fn new(id: u64, chunks: Vec<String>, identity: String, command: String, now: Duration) -> Self {
         Self {
             id,
             chunks,
             cycle: 0,
             active: true,
             identity,
+            command,
+            started_at: now,
+            ended_at: None,
+            pieces_dealt: 0,
+            lines_at_start: 0,
+            score_at_start: 0,
+            output: None,
+            repeats: 0,
+            next_repeat: Duration::ZERO,
         }
     }
 
-    fn next_cycle_pieces(&mut self) -> (u64, Vec<Piece>) {
-        self.cycle = self.cycle.wrapping_add(1);
-        let mut pieces = Vec::new();
-        for chunk in &self.chunks {
-            let payload = commands::chunk_to_payload(chunk);
-            let shape = random_shape();
-            pieces.push(Piece::with_payload(shape, payload));
-        }
+    // The whole command, once, as it starts. What follows is paced from here.
+    fn first_pass(
+        &mut self,
+        clock: Duration,
+        feed: Feed,
+        rng: &mut StdRng,
+        source: &mut dyn PieceSource,
+    ) -> (u64, Vec<Piece>) {
+        self.cycle = 1;
+        self.next_repeat = clock + feed.every();
+        let pieces = (0..self.chunks.len()).map(|i| self.chunk_piece(i, rng, source)).collect();
         (self.cycle, pieces)
     }
+
+    // A long build deals more than a quick one: while the command runs it deals one more piece
+    // per `Feed::every` of play, not a whole pass whenever the queue runs dry.
+    fn repeat_due(&self, clock: Duration) -> bool {
+        self.active && !self.chunks.is_empty() && clock >= self.next_repeat
+    }
+
+    // The next chunk round again, or a heartbeat. Each heartbeat counts as a cycle of its own,
+    // so one still queued at END is dropped with the rest.
+    fn next_repeat_piece(
+        &mut self,
+        clock: Duration,
+        feed: Feed,
+        rng: &mut StdRng,
+        source: &mut dyn PieceSource,
+    ) -> (u64, Piece) {
+        self.next_repeat = clock + feed.every();
+        if let Feed::Trickle { .. } = feed {
+            self.cycle = self.cycle.wrapping_add(1);
+            let payload = vec!['░'; CHUNK_SIZE];
+            let shape = source.next_shape(rng, &payload);
+            return (self.cycle, Piece::with_payload(shape, payload));
+        }
+        let i = self.repeats % self.chunks.len();
+        if i == 0 {
+            self.cycle = self.cycle.wrapping_add(1);
+        }
+        self.repeats += 1;
+        (self.cycle, self.chunk_piece(i, rng, source))
+    }
+
+    fn chunk_piece(&self, i: usize, rng: &mut StdRng, source: &mut dyn PieceSource) -> Piece {
+        let payload = commands::chunk_to_payload(&self.chunks[i]);
+        let shape = source.next_shape(rng, &payload);
+        Piece::with_payload(shape, payload).with_origin(Origin::Command { run: self.id, chunk: i })
+    }
+
+    // The output line cut into bars that read left to right as they fall, at most
+    // RESULT_PIECES of them.
+    fn result_pieces(&self) -> Vec<Piece> {
+        let Some(line) = self.output.as_deref() else {
+            return Vec::new();
+        };
+        commands::chunk_token(line)
+            .iter()
+            .take(RESULT_PIECES)
+            .map(|chunk| {
+                Piece::with_payload(Shape::I, commands::chunk_to_payload(chunk))
+                    .with_origin(Origin::Result { run: self.id })
+            })
+            .collect()
+    }
 }
 
+// One representation for snapshots, replays and `QUERY GAME`: board, falling piece, queue and
+// the rest. What only makes sense in this process (running commands, timers, the random
+// stream) is left out and starts fresh on load.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     pub board: Board,
     pub current: Piece,
@@ -59,22 +204,97 @@ pub struct Game {
     pub clear_flash_frames: u8,
     pub lock_flash_cells: Vec<(usize, usize)>,
     pub lock_flash_frames: u8,
-    piece_queue: VecDeque<QueuedPiece>,
+    pub level_up_frames: u8,
+    // Consecutive locks that cleared at least one line; `combo_fade` counts down after a break.
+    pub combo: u32,
+    pub last_combo: u32,
+    pub combo_fade: u8,
+    #[serde(skip)]
+    held_inputs: Vec<Action>,
+    // The autopilot already lined up the current piece.
+    #[serde(skip)]
+    pub(super) steered: bool,
+    pub(super) piece_queue: VecDeque<QueuedPiece>,
     pub active_piece: bool,
     active_run: Option<u64>,
-    active_runs: HashMap<u64, CommandRun>,
+    // Ordered so dealing from several runs is reproducible under a fixed seed.
+    #[serde(skip)]
+    pub(super) active_runs: BTreeMap<u64, CommandRun>,
     pub bombs: i32,
     pub current_is_bomb: bool,
     pub variety_meter: i32,
     pub last_cmd_identity: Option<String>,
     pub variety_streak: i32,
+    pub stats: RunStats,
+    pub mode: Mode,
+    pub profile: Profile,
+    // Play time, advanced only by `step`: pauses don't count, and it stops at game over.
+    #[serde(default)]
+    clock: Duration,
+    // Play time the recording hasn't logged yet.
+    #[serde(skip)]
+    pub(super) unlogged_clock: Duration,
+    #[serde(skip)]
+    gravity_timer: FixedStep,
+    #[serde(skip, default = "effect_timer")]
+    effect_timer: FixedStep,
+    // Bumped on every visible change so the renderer can skip identical frames.
+    #[serde(skip)]
+    revision: u64,
+    // Every random choice comes from here, so a seed pins down the whole game.
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(skip, default = "unseeded_rng")]
+    pub(super) rng: StdRng,
+    #[serde(skip)]
+    pub(super) recording: Option<Recording>,
+    #[serde(skip)]
+    pub(super) subscribers: Subscribers,
+    // Picks each piece's shape; not saved, so a loaded game deals from whatever it resumes.
+    #[serde(skip)]
+    pub(super) source: Source,
+    // Scores clears and fills the variety meter; not saved either.
+    #[serde(skip)]
+    pub(super) rules: Rules,
+    // Kicks to use instead of the mode's rotation system.
+    #[serde(skip)]
+    kicks: Option<KickTable>,
+    // What running commands deal after their first pass; not saved.
+    #[serde(skip)]
+    pub(super) feed: Feed,
+    // Times commands; not saved.
+    #[serde(skip)]
+    pub(super) wall: WallClock,
+}
+
+// A loaded game gets the random stream of whatever it resumes; this only stands in until then.
+// wasm32 has no entropy source without JS glue, so it falls back to a fixed stream there.
+fn unseeded_rng() -> StdRng {
+    #[cfg(not(target_arch = "wasm32"))]
+    return StdRng::from_entropy();
+    #[cfg(target_arch = "wasm32")]
+    return StdRng::seed_from_u64(0);
+}
+
+// Not on wasm32: pick the seed in the frontend and use `with_seed`.
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
+    // Random seeds stay in u32 range: short enough to read off the screen and pass back to
+    // --seed.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn new() -> Self {
-        let board = Board::new(BOARD_W, BOARD_H);
-        let game = Self {
-            board,
+        Self::with_seed(u64::from(rand::random::<u32>()))
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            board: Board::new(BOARD_W, BOARD_H),
             current: Piece::with_payload(Shape::I, vec!['░'; CHUNK_SIZE]),
             game_over: false,
             score: 0,
@@ -83,59 +303,228 @@ impl Game {
             clear_flash_frames: 0,
             lock_flash_cells: Vec::new(),
             lock_flash_frames: 0,
+            level_up_frames: 0,
+            combo: 0,
+            last_combo: 0,
+            combo_fade: 0,
+            held_inputs: Vec::new(),
+            steered: false,
             piece_queue: VecDeque::new(),
             active_piece: false,
             active_run: None,
-            active_runs: HashMap::new(),
+            active_runs: BTreeMap::new(),
             bombs: 0,
             current_is_bomb: false,
             variety_meter: 0,
             last_cmd_identity: None,
             variety_streak: 0,
-        };
-        game
+            stats: RunStats::default(),
+            mode: Mode::default(),
+            profile: Profile::default(),
+            clock: Duration::ZERO,
+            unlogged_clock: Duration::ZERO,
+            gravity_timer: FixedStep::default(),
+            effect_timer: effect_timer(),
+            revision: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            recording: None,
+            subscribers: Subscribers::default(),
+            source: Source::default(),
+            rules: Rules::default(),
+            kicks: None,
+            feed: Feed::default(),
+            wall: WallClock::default(),
+        }
     }
 
-    pub fn can_place(&self, piece: &Piece) -> bool {
-        for (x, y, _) in piece.cells() {
-            if x < 0 || y < 0 {
-                return false;
-            }
-            let (xu, yu) = (x as usize, y as usize);
-            if xu >= self.board.width || yu >= self.board.height {
-                return false;
-            }
-            if let Cell::Filled(_, _) = self.board.get(xu, yu) {
-                return false;
-            }
+    // Begin a run in the given mode; the clock starts now.
+    pub fn start(&mut self, mode: Mode, profile: Profile) {
+        self.record(Step::Start { mode, profile });
+        self.begin(mode, profile);
+    }
+
+    pub(super) fn begin(&mut self, mode: Mode, profile: Profile) {
+        log::info!("start {} / {}", mode.name(), profile.name());
+        self.touch();
+        self.mode = mode;
+        self.profile = profile;
+        self.clock = Duration::ZERO;
+        for run in self.active_runs.values_mut() {
+            run.next_repeat = self.feed.every();
+        }
+        self.gravity_timer = FixedStep::new(self.gravity_interval());
+        self.effect_timer = effect_timer();
+        if !self.active_piece {
+            self.spawn_next();
         }
-        true
+        self.debug_assert_invariants();
+    }
+
+    pub fn level(&self) -> u64 {
+        1 + self.lines_cleared / LINES_PER_LEVEL
+    }
+
+    // Each level shaves 15% off the profile's base interval, down to a floor.
+    pub fn gravity_interval(&self) -> Duration {
+        let base = self.profile.gravity_interval().as_secs_f64();
+        let scaled = base * 0.85f64.powi((self.level() - 1).min(30) as i32);
+        Duration::from_secs_f64(scaled.max(0.06))
+    }
+
+    pub fn play_time(&self) -> Duration {
+        self.clock
+    }
+
+    // Whether the run ended by meeting the mode's goal rather than topping out.
+    pub fn goal_reached(&self) -> bool {
+        match self.mode {
+            Mode::Sprint => self.lines_cleared >= SPRINT_LINES,
+            Mode::Ultra => self.play_time() >= Duration::from_secs(ULTRA_SECS),
+            Mode::Classic | Mode::Zen => false,
+        }
+    }
+
+    pub(super) fn finish(&mut self) {
+        self.touch();
+        if !self.game_over {
+            log::info!("game over: score {} lines {}", self.score, self.lines_cleared);
+            self.game_over = true;
+            self.emit(GameEvent::GameOver {
+                score: self.score,
+                lines: self.lines_cleared,
+                goal_reached: self.goal_reached(),
+            });
+        }
+    }
+
+    // Fresh board, but commands still running keep feeding pieces into it.
+    pub fn restart(&mut self) {
+        let mut runs = std::mem::take(&mut self.active_runs);
+        runs.retain(|_, run| run.active);
+        // Each game gets a seed of its own, so the one on screen always replays it with
+        // --seed. It's drawn from the last game's stream, which keeps a replayed session
+        // restarting the same way.
+        let mut fresh = Self::with_seed(u64::from(self.rng.next_u32()));
+        fresh.active_runs = runs;
+        fresh.revision = self.revision;
+        (fresh.mode, fresh.profile) = (self.mode, self.profile);
+        self.source.0.new_game();
+        // Each game is a replay of its own, starting from what the last one left running.
+        let recording = self.recording.take().is_some();
+        self.take_over(fresh);
+        if recording {
+            let start = self.clone();
+            self.start_recording();
+            self.record(Step::Restarted(Box::new(start)));
+        }
+        self.begin(self.mode, self.profile);
+    }
+
+    // Become `next`, keeping this game's settings, listeners and replay log. The random
+    // stream starts over from `next`'s seed.
+    pub(super) fn take_over(&mut self, mut next: Game) {
+        next.rng = StdRng::seed_from_u64(next.seed);
+        next.recording = self.recording.take();
+        next.subscribers = std::mem::take(&mut self.subscribers);
+        next.source = std::mem::take(&mut self.source);
+        next.rules = std::mem::take(&mut self.rules);
+        next.kicks = self.kicks.take();
+        next.feed = self.feed;
+        next.wall = std::mem::take(&mut self.wall);
+        *self = next;
+    }
+
+    // Pick a saved game back up. Its play time carries on, so Ultra's three minutes and a
+    // Sprint's time go on from where they stopped. Commands started while the prompt was up
+    // keep running into it, their repeats paced from that play time.
+    pub fn resume(&mut self, saved: Game) {
+        self.record(Step::Resume(Box::new(saved.clone())));
+        log::info!("resuming saved game: score {}", saved.score);
+        let mut queue = std::mem::take(&mut self.piece_queue);
+        let runs = std::mem::take(&mut self.active_runs);
+        let recording = self.recording.take();
+        let subscribers = std::mem::take(&mut self.subscribers);
+        let source = std::mem::take(&mut self.source);
+        let rules = std::mem::take(&mut self.rules);
+        let kicks = self.kicks.take();
+        let feed = self.feed;
+        let wall = std::mem::take(&mut self.wall);
+        let (revision, seed) = (self.revision, self.seed);
+        let mut saved = saved;
+        std::mem::swap(&mut saved.rng, &mut self.rng);
+        *self = saved;
+        self.piece_queue.append(&mut queue);
+        self.active_runs = runs;
+        self.recording = recording;
+        self.subscribers = subscribers;
+        self.source = source;
+        self.rules = rules;
+        self.kicks = kicks;
+        self.feed = feed;
+        self.wall = wall;
+        self.revision = revision;
+        self.seed = seed;
+        // Don't let a piece saved mid-air overlap whatever the board became.
+        if self.active_piece && !self.can_place(&self.current) {
+            self.spawn_next();
+        }
+        let clock = self.clock;
+        self.begin(self.mode, self.profile);
+        self.clock = clock;
+        let next_repeat = clock + self.feed.every();
+        for run in self.active_runs.values_mut() {
+            run.next_repeat = next_repeat;
+        }
+    }
+
+    // Something a pane kill would lose.
+    pub fn worth_saving(&self) -> bool {
+        !self.game_over && (self.score > 0 || !self.board.is_empty())
+    }
+
+    pub fn can_place(&self, piece: &Piece) -> bool {
+        piece
+            .cells()
+            .into_iter()
+            .all(|(x, y, _)| self.board.contains(x, y) && !self.board.is_filled(x as usize, y as usize))
     }
 
     pub fn lock_piece(&mut self) {
         self.lock_flash_cells.clear();
+        let origin = self.current.origin;
         for (x, y, (left, right)) in self.current.cells_with_pairs() {
-            if x >= 0 && y >= 0 {
+            if self.board.contains(x, y) {
                 let (xu, yu) = (x as usize, y as usize);
-                if xu < self.board.width && yu < self.board.height {
-                    self.board.set(xu, yu, Cell::Filled(left, right));
-                    self.lock_flash_cells.push((xu, yu));
-                }
+                self.board.set(xu, yu, Cell::Filled(left, right, origin));
+                self.lock_flash_cells.push((xu, yu));
             }
         }
         self.lock_flash_frames = 1;
-        self.active_run = None;
+        self.stats.pieces_placed += 1;
+        let locked_run = self.active_run.take();
         self.active_piece = false;
-        let full_rows: Vec<usize> = (0..self.board.height)
-            .filter(|y| (0..self.board.width).all(|x| matches!(self.board.get(x, *y), Cell::Filled(_, _))))
-            .collect();
+        self.emit(GameEvent::PieceLocked {
+            run: locked_run,
+            bomb: self.current_is_bomb,
+        });
+        // The blast goes first: a row the bomb completed is blown open, not cleared.
+        if self.current_is_bomb {
+            self.apply_bomb_clear();
+        }
+        let full_rows = self.board.full_rows();
         if !full_rows.is_empty() {
+            if let Some(identity) = locked_run.and_then(|id| self.identity_for_run(id)) {
+                let identity = identity.to_string();
+                self.stats.credit(&identity).lines += full_rows.len() as u32;
+            }
             self.pending_clear = full_rows;
             self.clear_flash_frames = 2;
-        }
-
-        if self.current_is_bomb {
-            self.apply_bomb_clear();
+            self.combo += 1;
+        } else if self.combo > 0 {
+            self.last_combo = self.combo;
+            self.combo = 0;
+            self.combo_fade = 12;
         }
     }
 
@@ -146,6 +535,7 @@ impl Game {
         let next = self.current.shifted(dx, dy);
         if self.can_place(&next) {
             self.current = next;
+            self.touch();
             true
         } else {
             false
@@ -156,26 +546,105 @@ impl Game {
         if self.game_over {
             return false;
         }
-        let next = self.current.rotated();
-        if self.can_place(&next) {
-            self.current = next;
-            true
-        } else {
-            false
+        match self.turn(&self.current) {
+            Some(next) => {
+                self.current = next;
+                self.touch();
+                true
+            }
+            None => false,
         }
     }
 
+    // Where `piece` ends up after a quarter turn, trying each kick in order; None if blocked.
+    pub(super) fn turn(&self, piece: &Piece) -> Option<Piece> {
+        let turned = piece.rotated();
+        let kicks = match &self.kicks {
+            Some(table) => table.get(piece.shape, piece.rotation),
+            None => piece.shape.kicks(piece.rotation, self.mode.rotation_system()),
+        };
+        std::iter::once(&(0, 0))
+            .chain(kicks)
+            .map(|&(dx, dy)| turned.shifted(dx, dy))
+            .find(|next| self.can_place(next))
+    }
+
+    // Rotate by `table` from now on instead of by the mode's rotation system; `None` goes back
+    // to the mode's. Carried through `restart` and `resume` like the piece source.
+    pub fn set_kick_table(&mut self, table: Option<KickTable>) {
+        self.kicks = table;
+    }
+
+    // Advance the game by `elapsed` of play, after applying `action` if there is one. Gravity
+    // and effect frames fire on the game's own clock, so the same actions at the same elapsed
+    // times always play out the same game.
+    pub fn step(&mut self, action: Option<Action>, elapsed: Duration) {
+        if let Some(input) = action {
+            self.input(input);
+        }
+        self.advance_clock(elapsed);
+        for _ in 0..self.effect_timer.advance(elapsed) {
+            self.process_effects();
+        }
+        self.gravity_timer.set_step(self.gravity_interval());
+        for _ in 0..self.gravity_timer.advance(elapsed) {
+            if self.game_over {
+                break;
+            }
+            self.tick_gravity();
+        }
+        self.debug_assert_invariants();
+    }
+
+    pub(super) fn advance_clock(&mut self, elapsed: Duration) {
+        if self.game_over {
+            return;
+        }
+        self.clock += elapsed;
+        if self.recording.is_some() {
+            self.unlogged_clock += elapsed;
+        }
+    }
+
+    // Forget banked time, so a game coming back from a pause gets a full gravity interval
+    // instead of an instant drop.
+    pub fn reset_timers(&mut self) {
+        self.gravity_timer.reset();
+        self.effect_timer.reset();
+    }
+
+    // Play time until gravity, or an animation still playing, next needs a step.
+    pub fn until_next_tick(&self) -> Duration {
+        let mut wait = self.gravity_timer.until_next();
+        if self.animating() {
+            wait = wait.min(self.effect_timer.until_next());
+        }
+        wait
+    }
+
     pub fn tick_gravity(&mut self) {
+        self.record(Step::Gravity);
         if self.game_over {
             return;
         }
+        if self.mode == Mode::Ultra && self.goal_reached() {
+            self.finish();
+            return;
+        }
+        // Nothing falling: a running command may have a repeat come due since.
         if !self.active_piece {
+            if self.active_runs.values().any(|run| run.repeat_due(self.clock)) {
+                self.spawn_next();
+                self.touch();
+            }
             return;
         }
         if !self.move_current(0, 1) {
             self.lock_piece();
             self.spawn_next();
+            self.touch();
         }
+        self.debug_assert_invariants();
     }
 
     pub fn hard_drop(&mut self) {
@@ -188,30 +657,86 @@ impl Game {
         while self.move_current(0, 1) {}
         self.lock_piece();
         self.spawn_next();
+        self.touch();
+        self.debug_assert_invariants();
+    }
+
+    pub fn input(&mut self, input: Action) {
+        self.record(Step::Input(input));
+        if !self.pending_clear.is_empty() {
+            if self.held_inputs.len() < MAX_HELD_INPUTS {
+                self.held_inputs.push(input);
+            }
+            return;
+        }
+        self.apply_input(input);
+        self.debug_assert_invariants();
+    }
+
+    fn apply_input(&mut self, input: Action) {
+        match input {
+            Action::Left => {
+                self.move_current(-1, 0);
+            }
+            Action::Right => {
+                self.move_current(1, 0);
+            }
+            Action::SoftDrop => {
+                self.move_current(0, 1);
+            }
+            Action::Rotate => {
+                self.rotate_current();
+            }
+            Action::HardDrop => self.hard_drop(),
+        }
     }
 
     pub fn process_effects(&mut self) {
+        if self.animating() {
+            self.record(Step::Effects);
+            self.touch();
+        }
         if self.lock_flash_frames > 0 {
             self.lock_flash_frames -= 1;
         }
+        if self.level_up_frames > 0 {
+            self.level_up_frames -= 1;
+        }
+        if self.combo_fade > 0 {
+            self.combo_fade -= 1;
+        }
         if self.clear_flash_frames > 0 {
             self.clear_flash_frames -= 1;
             if self.clear_flash_frames == 0 && !self.pending_clear.is_empty() {
                 self.perform_pending_clear();
             }
         }
+        self.debug_assert_invariants();
     }
 
     pub fn spawn_next(&mut self) {
         self.ensure_queue();
+        self.steered = false;
         if let Some(qp) = self.piece_queue.pop_front() {
             self.active_piece = true;
             self.active_run = if qp.is_bomb { None } else { Some(qp.run_id) };
             self.current_is_bomb = qp.is_bomb;
+            if !qp.is_bomb
+                && let Some(run) = self.active_runs.get_mut(&qp.run_id)
+            {
+                run.pieces_dealt += 1;
+                let identity = run.identity.clone();
+                self.stats.credit(&identity).pieces += 1;
+            }
             if self.can_place(&qp.piece) {
                 self.current = qp.piece;
+            } else if self.mode == Mode::Zen {
+                // Zen never ends: wipe the stack and keep going.
+                log::info!("zen top-out: board wiped");
+                self.board = Board::new(self.board.width, self.board.height);
+                self.current = qp.piece;
             } else {
-                self.game_over = true;
+                self.finish();
             }
         } else {
             self.active_piece = false;
@@ -220,7 +745,8 @@ impl Game {
         }
     }
 
-    pub(crate) fn ghost_piece(&self) -> Piece {
+    // Where the falling piece would land if dropped now.
+    pub fn ghost_piece(&self) -> Piece {
         let mut ghost = self.current.clone();
         while {
             let next = ghost.shifted(0, 1);
@@ -232,12 +758,18 @@ impl Game {
     }
 
     pub fn handle_command_event(&mut self, ev: CommandEvent) {
+        self.record(Step::Command(ev.clone()));
+        self.touch();
         match ev {
             CommandEvent::Start { id, command } => {
                 let chunks = commands::command_to_chunks(&command);
                 let identity = command_identity(&command);
-                let mut run = CommandRun::new(id, chunks, identity.clone());
-                let (cycle, pieces) = run.next_cycle_pieces();
+                let mut run =
+                    CommandRun::new(id, chunks, identity.clone(), command, self.wall.now());
+                run.lines_at_start = self.lines_cleared;
+                run.score_at_start = self.score;
+                let (cycle, pieces) =
+                    run.first_pass(self.clock, self.feed, &mut self.rng, self.source.0.as_mut());
                 for p in pieces {
                     self.piece_queue.push_back(QueuedPiece {
                         run_id: id,
@@ -247,6 +779,7 @@ impl Game {
                     });
                 }
                 self.active_runs.insert(id, run);
+                self.stats.commands_played += 1;
                 self.last_cmd_identity.get_or_insert(identity);
                 if !self.active_piece {
                     self.spawn_next();
@@ -256,21 +789,175 @@ impl Game {
                 let identity = self.active_runs.get(&id).map(|r| r.identity.clone());
                 if let Some(run) = self.active_runs.get_mut(&id) {
                     run.active = false;
+                    let now = self.wall.now();
+                    run.ended_at = Some(now);
+                    self.stats
+                        .record_command_end(&run.command, now.saturating_sub(run.started_at));
                 }
-                // Drop queued pieces from repeat cycles for this run.
-                self.piece_queue
-                    .retain(|qp| qp.run_id != id || qp.cycle <= 1);
+                let exit = Exit::from_code(_exit_code);
+                // Drop queued pieces from repeat cycles for this run; an interrupted one loses
+                // the rest of its first pass too.
+                self.piece_queue.retain(|qp| {
+                    qp.is_bomb || qp.run_id != id || (qp.cycle <= 1 && exit != Exit::Interrupted)
+                });
 
-                if _exit_code != 0 {
-                    self.apply_garbage_row();
+                let rows = match exit {
+                    Exit::Failed => 1,
+                    Exit::Crashed => CRASH_GARBAGE,
+                    Exit::Success | Exit::Interrupted => 0,
+                };
+                if rows > 0 && self.mode.punishes_failures() {
+                    log::debug!("run {id} failed ({_exit_code}): {rows} garbage + infection");
+                    for _ in 0..rows {
+                        if self.game_over {
+                            break;
+                        }
+                        self.apply_garbage_row();
+                        self.emit(GameEvent::GarbageAdded { run: id });
+                        if let Some(id_str) = &identity {
+                            self.stats.credit(id_str).garbage += 1;
+                        }
+                    }
                     self.apply_infection();
                 }
                 if let Some(id_str) = identity {
                     self.apply_variety(&id_str, _exit_code);
                     self.last_cmd_identity = Some(id_str);
                 }
+                // Next to fall, first chunk first. Cycle 0 keeps them ahead of the run's own.
+                let results = self
+                    .active_runs
+                    .get(&id)
+                    .filter(|_| exit != Exit::Interrupted)
+                    .map(CommandRun::result_pieces);
+                for piece in results.unwrap_or_default().into_iter().rev() {
+                    self.piece_queue.push_front(QueuedPiece {
+                        run_id: id,
+                        cycle: 0,
+                        piece,
+                        is_bomb: false,
+                    });
+                }
+                if !self.game_over && !self.active_piece {
+                    self.spawn_next();
+                }
+            }
+            CommandEvent::Output { id, line } => {
+                let line = line.trim();
+                if let Some(run) = self.active_runs.get_mut(&id)
+                    && !line.is_empty()
+                {
+                    run.output = Some(line.to_string());
+                }
+            }
+        }
+        self.debug_assert_invariants();
+    }
+
+    // Full text of the command the falling piece was cut from.
+    pub fn current_command(&self) -> Option<&str> {
+        if !self.active_piece {
+            return None;
+        }
+        self.active_run
+            .and_then(|id| self.active_runs.get(&id))
+            .map(|run| run.command.as_str())
+    }
+
+    // How long the falling piece's command has been running (or ran, once it ended).
+    pub fn current_command_elapsed(&self) -> Option<Duration> {
+        let run = self.active_run.and_then(|id| self.active_runs.get(&id))?;
+        Some(run.ended_at.unwrap_or_else(|| self.wall.now()).saturating_sub(run.started_at))
+    }
+
+    pub fn run_elapsed(&self, id: u64) -> Option<Duration> {
+        let run = self.active_runs.get(&id)?;
+        Some(run.ended_at.unwrap_or_else(|| self.wall.now()).saturating_sub(run.started_at))
+    }
+
+    // Pieces a command has produced: those already dealt plus its pieces still queued.
+    pub fn pieces_for_run(&self, id: u64) -> u32 {
+        let dealt = self.active_runs.get(&id).map_or(0, |run| run.pieces_dealt);
+        let queued = self.piece_queue.iter().filter(|qp| qp.run_id == id && !qp.is_bomb).count();
+        dealt + queued as u32
+    }
+
+    // What the board did while a command ran: pieces it dealt, lines and points gained since it
+    // started.
+    pub fn run_recap(&self, id: u64) -> Option<String> {
+        let run = self.active_runs.get(&id)?;
+        Some(format!(
+            "{} pieces, {} lines, +{}",
+            run.pieces_dealt,
+            self.lines_cleared.saturating_sub(run.lines_at_start),
+            self.score.saturating_sub(run.score_at_start),
+        ))
+    }
+
+    pub fn command_for_run(&self, id: u64) -> Option<&str> {
+        self.active_runs.get(&id).map(|run| run.command.as_str())
+    }
+
+    // The last output line the hook reported for a command.
+    pub fn output_for_run(&self, id: u64) -> Option<&str> {
+        self.active_runs.get(&id).and_then(|run| run.output.as_deref())
+    }
+
+    pub fn identity_for_run(&self, id: u64) -> Option<&str> {
+        self.active_runs.get(&id).map(|run| run.identity.as_str())
+    }
+
+    // Command runs with cells on the board or in the falling piece, in first-seen order.
+    pub fn visible_runs(&self) -> Vec<u64> {
+        let mut runs = Vec::new();
+        let falling = self.active_piece.then_some(self.current.origin);
+        let locked = self.board.iter_filled().filter_map(|(_, _, cell)| match cell {
+            Cell::Filled(_, _, origin) => Some(origin),
+            Cell::Empty => None,
+        });
+        for origin in falling.into_iter().chain(locked) {
+            if let Origin::Command { run, .. } | Origin::Result { run } = origin
+                && !runs.contains(&run)
+            {
+                runs.push(run);
             }
         }
+        runs
+    }
+
+    pub fn chunk_for_run(&self, id: u64, chunk: usize) -> Option<&str> {
+        self.active_runs
+            .get(&id)
+            .and_then(|run| run.chunks.get(chunk))
+            .map(String::as_str)
+    }
+
+    pub fn queued_pieces(&self) -> usize {
+        self.piece_queue.len()
+    }
+
+    pub fn active_run_count(&self) -> usize {
+        self.active_runs.values().filter(|r| r.active).count()
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn touch(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    // Frame-counted effects (flashes, level-up, combo fade) still playing out.
+    pub fn animating(&self) -> bool {
+        self.clear_flash_frames > 0
+            || self.lock_flash_frames > 0
+            || self.level_up_frames > 0
+            || self.combo_fade > 0
+    }
+
+    pub fn in_danger(&self) -> bool {
+        !self.game_over && self.board.max_height() + DANGER_ROWS >= self.board.height
     }
 
     pub fn is_running(&self) -> bool {
@@ -284,16 +971,19 @@ impl Game {
             return;
         }
         for run in self.active_runs.values_mut() {
-            if run.active {
-                let (cycle, pieces) = run.next_cycle_pieces();
-                for p in pieces {
-                    self.piece_queue.push_back(QueuedPiece {
-                        run_id: run.id,
-                        cycle,
-                        piece: p,
-                        is_bomb: false,
-                    });
-                }
+            if run.repeat_due(self.clock) {
+                let (cycle, piece) = run.next_repeat_piece(
+                    self.clock,
+                    self.feed,
+                    &mut self.rng,
+                    self.source.0.as_mut(),
+                );
+                self.piece_queue.push_back(QueuedPiece {
+                    run_id: run.id,
+                    cycle,
+                    piece,
+                    is_bomb: false,
+                });
             }
         }
         if self.piece_queue.is_empty() && self.bombs > 0 {
@@ -305,23 +995,23 @@ impl Game {
                 is_bomb: true,
             });
             self.bombs -= 1;
+            self.stats.bombs_used += 1;
+        }
+        if self.piece_queue.is_empty() && self.mode.self_feeding() {
+            let payload = vec!['░'; CHUNK_SIZE];
+            let shape = self.source.0.next_shape(&mut self.rng, &payload);
+            self.piece_queue.push_back(QueuedPiece {
+                run_id: 0,
+                cycle: 0,
+                piece: Piece::with_payload(shape, payload),
+                is_bomb: false,
+            });
         }
-    }
-
-    fn add_score(&mut self, cleared: u64) {
-        let add = match cleared {
-            1 => 100,
-            2 => 300,
-            3 => 500,
-            4 => 800,
-            _ => 0,
-        };
-        self.score += add;
     }
 
     fn make_bomb_piece() -> Piece {
         // Use O piece for compact 2x2 bomb footprint with solid payload.
-        Piece::with_payload(Shape::O, vec!['▓'; CHUNK_SIZE])
+        Piece::with_payload(Shape::O, vec!['▓'; CHUNK_SIZE]).with_origin(Origin::Bomb)
     }
 
     fn perform_pending_clear(&mut self) {
@@ -329,27 +1019,37 @@ impl Game {
         if cleared == 0 {
             return;
         }
-        let mut new_cells = Vec::with_capacity(self.board.cells.len());
-        for y in 0..self.board.height {
-            if self.pending_clear.contains(&y) {
-                continue;
-            }
-            for x in 0..self.board.width {
-                new_cells.push(self.board.get(x, y));
-            }
-        }
-        for _ in 0..cleared {
-            for _ in 0..self.board.width {
-                new_cells.insert(0, Cell::Empty);
-            }
+        // Top down, so taking a row out never moves one still to go.
+        self.pending_clear.sort_unstable();
+        for &y in &self.pending_clear {
+            self.board.clear_row(y);
         }
-        self.board.cells = new_cells;
+        let level_before = self.level();
         self.lines_cleared += cleared;
-        self.add_score(cleared);
+        self.stats.biggest_clear = self.stats.biggest_clear.max(cleared as u32);
+        if self.level() > level_before {
+            log::debug!("level {}", self.level());
+            self.level_up_frames = 20;
+        }
+        self.score += self.rules.0.clear_points(cleared as u32, level_before, self.combo);
         self.pending_clear.clear();
+        self.emit(GameEvent::LinesCleared {
+            count: cleared as u32,
+            combo: self.combo,
+        });
+        if self.mode == Mode::Sprint && self.goal_reached() {
+            self.finish();
+        }
+        for input in std::mem::take(&mut self.held_inputs) {
+            self.apply_input(input);
+        }
     }
 }
 
+fn effect_timer() -> FixedStep {
+    FixedStep::new(EFFECT_FRAME)
+}
+
 fn command_identity(cmd: &str) -> String {
     commands::tokenize_command(cmd)
         .into_iter()
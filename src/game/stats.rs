@@ -0,0 +1,261 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{CommandEvent, Game};
+
+// Per-game tallies surfaced on the game-over screen.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub bombs_used: u32,
+    #[serde(default)]
+    pub bombs_earned: u32,
+    #[serde(default)]
+    pub pieces_placed: u32,
+    // Most rows taken by a single clear.
+    #[serde(default)]
+    pub biggest_clear: u32,
+    pub commands_played: u32,
+    pub longest_command: Option<(String, Duration)>,
+    #[serde(default)]
+    pub by_command: BTreeMap<String, Contribution>,
+}
+
+// What one command identity has fed into the game so far.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Contribution {
+    pub pieces: u32,
+    pub lines: u32,
+    pub garbage: u32,
+    pub bombs: u32,
+}
+
+impl RunStats {
+    pub(crate) fn record_command_end(&mut self, command: &str, elapsed: Duration) {
+        let longer = self
+            .longest_command
+            .as_ref()
+            .is_none_or(|(_, best)| elapsed > *best);
+        if longer {
+            self.longest_command = Some((command.to_string(), elapsed));
+        }
+    }
+
+    pub(crate) fn credit(&mut self, identity: &str) -> &mut Contribution {
+        self.by_command.entry(identity.to_string()).or_default()
+    }
+
+    // Identities ranked by lines, then pieces, for the command leaderboard.
+    pub fn leaderboard(&self) -> Vec<(&str, Contribution)> {
+        let mut rows: Vec<(&str, Contribution)> = self
+            .by_command
+            .iter()
+            .map(|(identity, c)| (identity.as_str(), *c))
+            .collect();
+        rows.sort_by_key(|(_, c)| Reverse((c.lines, c.pieces)));
+        rows
+    }
+
+    // One-line recap kept alongside a high score.
+    pub fn summary(&self) -> String {
+        let commands = format!("{} cmds", self.commands_played);
+        match &self.longest_command {
+            Some((command, _)) => format!("{commands}, longest {command}"),
+            None => commands,
+        }
+    }
+}
+
+// Wall-clock totals for the whole session, across restarts: how long the game has been up and
+// how much of that had at least one command running.
+pub struct SessionClock {
+    started_at: Instant,
+    running: HashSet<u64>,
+    busy_since: Option<Instant>,
+    busy_total: Duration,
+}
+
+impl Default for SessionClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionClock {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            running: HashSet::new(),
+            busy_since: None,
+            busy_total: Duration::ZERO,
+        }
+    }
+
+    pub fn observe(&mut self, ev: &CommandEvent) {
+        let now = Instant::now();
+        match ev {
+            CommandEvent::Start { id, .. } => {
+                self.running.insert(*id);
+                self.busy_since.get_or_insert(now);
+            }
+            CommandEvent::End { id, .. } => {
+                self.running.remove(id);
+                if self.running.is_empty()
+                    && let Some(since) = self.busy_since.take()
+                {
+                    self.busy_total += now - since;
+                }
+            }
+            CommandEvent::Output { .. } => {}
+        }
+    }
+
+    pub fn session_time(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn busy_time(&self) -> Duration {
+        self.busy_total + self.busy_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+// One shell command as the session export reports it; exit_code is None while it still runs.
+#[derive(Clone, Serialize)]
+pub struct CommandRecord {
+    #[serde(skip)]
+    pub id: u64,
+    pub command: String,
+    // Seconds into the session when it started.
+    pub started_secs: f64,
+    pub duration_secs: f64,
+    pub exit_code: Option<i32>,
+    // Pieces it produced, settled when it ends.
+    pub pieces: u32,
+}
+
+// Every command seen this session in start order, kept across restarts.
+pub struct CommandLog {
+    started_at: Instant,
+    records: Vec<CommandRecord>,
+    running: HashMap<u64, (usize, Instant)>,
+}
+
+impl Default for CommandLog {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            records: Vec::new(),
+            running: HashMap::new(),
+        }
+    }
+}
+
+impl CommandLog {
+    pub fn observe(&mut self, ev: &CommandEvent) {
+        match ev {
+            CommandEvent::Start { id, command } => {
+                self.running.insert(*id, (self.records.len(), Instant::now()));
+                self.records.push(CommandRecord {
+                    id: *id,
+                    command: command.clone(),
+                    started_secs: secs(self.started_at.elapsed()),
+                    duration_secs: 0.0,
+                    exit_code: None,
+                    pieces: 0,
+                });
+            }
+            CommandEvent::End { id, _exit_code } => {
+                if let Some((idx, started)) = self.running.remove(id) {
+                    let record = &mut self.records[idx];
+                    record.duration_secs = secs(started.elapsed());
+                    record.exit_code = Some(*_exit_code);
+                }
+            }
+            CommandEvent::Output { .. } => {}
+        }
+    }
+
+    pub fn set_pieces(&mut self, id: u64, pieces: u32) {
+        if let Some(record) = self.records.iter_mut().rev().find(|r| r.id == id) {
+            record.pieces = pieces;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    // Records with running commands' durations brought up to now.
+    pub fn records(&self) -> Vec<CommandRecord> {
+        let mut records = self.records.clone();
+        for (idx, started) in self.running.values() {
+            records[*idx].duration_secs = secs(started.elapsed());
+        }
+        records
+    }
+}
+
+// Millisecond precision is plenty for a command's wall time.
+fn secs(d: Duration) -> f64 {
+    d.as_millis() as f64 / 1000.0
+}
+
+// Totals over the games of a session. Finished games are folded in on restart; the live one is
+// added when the summary is taken.
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct SessionTotals {
+    pub games: u32,
+    pub score: u64,
+    pub lines: u64,
+    pub pieces: u32,
+    pub bombs_earned: u32,
+    pub bombs_used: u32,
+}
+
+impl SessionTotals {
+    pub fn add(&mut self, game: &Game) {
+        self.games += 1;
+        self.score += game.score;
+        self.lines += game.lines_cleared;
+        self.pieces += game.stats.pieces_placed;
+        self.bombs_earned += game.stats.bombs_earned;
+        self.bombs_used += game.stats.bombs_used;
+    }
+}
+
+// The game on screen, apart from the session's totals; what status lines poll QUERY for.
+#[derive(Serialize)]
+pub struct CurrentGame {
+    pub score: u64,
+    pub lines: u64,
+    pub running: usize,
+    pub game_over: bool,
+}
+
+impl CurrentGame {
+    pub fn of(game: &Game) -> Self {
+        Self {
+            score: game.score,
+            lines: game.lines_cleared,
+            running: game.active_run_count(),
+            game_over: game.game_over,
+        }
+    }
+}
+
+// What the JSON export and the socket QUERY reply contain.
+#[derive(Serialize)]
+pub struct SessionSummary {
+    pub session_secs: u64,
+    pub busy_secs: u64,
+    #[serde(flatten)]
+    pub totals: SessionTotals,
+    pub current: CurrentGame,
+    pub commands: Vec<CommandRecord>,
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+// Never replay more than this many steps at once, so a long stall doesn't dump the piece.
+const MAX_CATCH_UP: u32 = 5;
+
+// Fixed-timestep accumulator: elapsed time is banked and spent in whole steps.
+#[derive(Clone, Default)]
+pub(crate) struct FixedStep {
+    step: Duration,
+    banked: Duration,
+}
+
+impl FixedStep {
+    pub(crate) fn new(step: Duration) -> Self {
+        Self {
+            step,
+            banked: Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn set_step(&mut self, step: Duration) {
+        self.step = step;
+    }
+
+    // Bank `elapsed` and return how many whole steps are now due.
+    pub(crate) fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.banked += elapsed;
+        let mut steps = 0;
+        while self.banked >= self.step && steps < MAX_CATCH_UP {
+            self.banked -= self.step;
+            steps += 1;
+        }
+        if steps == MAX_CATCH_UP {
+            self.banked = self.banked.min(self.step);
+        }
+        steps
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.banked = Duration::ZERO;
+    }
+
+    pub(crate) fn until_next(&self) -> Duration {
+        self.step.saturating_sub(self.banked)
+    }
+}
@@ -0,0 +1,35 @@
+use std::env;
+use std::io::{self, Write};
+use std::process::Command;
+
+// OSC 52 asks the terminal to set the system clipboard. Inside tmux the pane's own escape is
+// only honoured with `set-clipboard on`, so hand the text to tmux instead: `set-buffer -w`
+// forwards it to the outer terminal with OSC 52 itself.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    if env::var_os("TMUX").is_some() {
+        let status = Command::new("tmux").args(["set-buffer", "-w", "--", text]).status()?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
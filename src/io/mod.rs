@@ -1,3 +1,21 @@
+// The sockets, the clipboard and notifications belong to the terminal game; the protocol is
+// plain text and needed by anything that feeds a `Game`.
+#[cfg(feature = "tui")]
+mod clipboard;
+#[cfg(feature = "tui")]
+mod notify;
+mod protocol;
+#[cfg(feature = "tui")]
 mod socket;
+#[cfg(feature = "tui")]
+mod watch;
 
-pub use socket::spawn_socket_listener;
+#[cfg(feature = "tui")]
+pub use clipboard::copy_to_clipboard;
+#[cfg(feature = "tui")]
+pub use notify::{desktop_notify, Osc};
+pub use protocol::parse_command_line;
+#[cfg(feature = "tui")]
+pub use socket::{mark_quit, remove_socket, spawn_socket_listener, Attach, Detach, Query, Quit};
+#[cfg(feature = "tui")]
+pub use watch::{connect_spectator, Spectator, Spectators};
@@ -0,0 +1,60 @@
+use std::io;
+use std::process::{Command, Stdio};
+use std::thread;
+
+// A desktop notification through the system's own notifier: `notify-send` (libnotify) on
+// Linux and the BSDs, `osascript` on macOS. Urgent ones stay up until dismissed where the
+// notifier supports it.
+pub fn desktop_notify(summary: &str, body: &str, urgent: bool) -> io::Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(summary)
+        );
+        let mut cmd = Command::new("osascript");
+        cmd.args(["-e", &script]);
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["--app-name=waitris", "--urgency", if urgent { "critical" } else { "normal" }]);
+        cmd.args(["--", summary, body]);
+        cmd
+    };
+    let mut child = cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+    // Reaped off the game thread; a busy session bus can hold the notifier up for a while.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Notification escapes the terminal itself turns into a desktop notification. OSC 9 (iTerm2,
+// WezTerm, kitty, Windows Terminal) carries one message; OSC 777 (urxvt, foot, Ghostty,
+// WezTerm) a title and a body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Osc {
+    Nine,
+    SevenSevenSeven,
+}
+
+impl Osc {
+    // The escape for one notification. Control characters would end it early, and a `;` in
+    // an OSC 777 title would start the body, so both become spaces.
+    pub fn notification(self, title: &str, body: &str) -> String {
+        match self {
+            Osc::Nine => format!("\x1b]9;{}: {}\x07", printable(title), printable(body)),
+            Osc::SevenSevenSeven => format!(
+                "\x1b]777;notify;{};{}\x07",
+                printable(title).replace(';', " "),
+                printable(body)
+            ),
+        }
+    }
+}
+
+fn printable(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
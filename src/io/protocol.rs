@@ -0,0 +1,39 @@
+use crate::CommandEvent;
+
+// One line from the shell hook: `START <id> <command>`, `OUTPUT <id> <line>` or
+// `END <id> <exit code>`. The socket hands over whatever a client sent, so this takes any
+// string, never panics, and allocates only the text it returns:
+// - surrounding whitespace is ignored; the keyword is case-sensitive and followed by a space;
+// - `<id>` is a u64 in decimal, else the line is rejected;
+// - the command or output line is the rest of the line, trimmed, and may be empty;
+// - the exit code is the next word as an i32, 0 if it is missing or not a number, and
+//   anything after it is ignored;
+// - any other line is rejected.
+pub fn parse_command_line(line: &str) -> Option<CommandEvent> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("START ") {
+        let mut parts = rest.splitn(2, ' ');
+        let id = parts.next()?.parse().ok()?;
+        let command = parts.next().unwrap_or("").trim();
+        return Some(CommandEvent::Start {
+            id,
+            command: command.to_string(),
+        });
+    }
+    if let Some(rest) = line.strip_prefix("OUTPUT ") {
+        let mut parts = rest.splitn(2, ' ');
+        let id = parts.next()?.parse().ok()?;
+        let line = parts.next().unwrap_or("").trim();
+        return Some(CommandEvent::Output {
+            id,
+            line: line.to_string(),
+        });
+    }
+    if let Some(rest) = line.strip_prefix("END ") {
+        let mut parts = rest.split_whitespace();
+        let id = parts.next()?.parse().ok()?;
+        let exit_code = parts.next().and_then(|code| code.parse().ok()).unwrap_or(0);
+        return Some(CommandEvent::End { id, _exit_code: exit_code });
+    }
+    None
+}
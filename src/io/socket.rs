@@ -1,55 +1,187 @@
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
+use std::net::Shutdown;
 use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::thread;
+use std::time::Duration;
 
-use crate::{CommandEvent, SOCKET_PATH};
+use crossterm::event::Event;
 
-pub fn spawn_socket_listener(tx: mpsc::Sender<CommandEvent>) {
-    let _ = fs::remove_file(SOCKET_PATH);
-    let listener = UnixListener::bind(SOCKET_PATH).ok();
+use crate::paths::{ensure_socket_dir, quit_marker_path, socket_path};
+use crate::CommandEvent;
+
+use super::parse_command_line;
+
+// How long a QUERY client waits for the game loop to answer.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+static BOUND: AtomicBool = AtomicBool::new(false);
+static NEXT_ATTACH: AtomicU64 = AtomicU64::new(1);
+
+// A client asking for the session summary, or with `QUERY GAME` for the game itself; the JSON
+// goes back down its connection.
+pub struct Query {
+    pub game: bool,
+    pub reply: mpsc::Sender<String>,
+}
+
+// A client asking the game to quit, as `waitris stop` does.
+pub struct Quit;
+
+// A UI attaching to a daemon (`stack-game --attach`): frames go down `stream`, and its
+// terminal events come up the same connection as EVENT lines.
+pub struct Attach {
+    pub id: u64,
+    pub stream: UnixStream,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Attach {
+    // Turn the UI away. Both halves are shut so its EVENT lines stop reaching the game too.
+    pub fn reject(mut self) {
+        let _ = writeln!(self.stream, "BUSY");
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+// That UI's connection closed.
+pub struct Detach {
+    pub id: u64,
+}
+
+pub fn spawn_socket_listener<T>(tx: mpsc::Sender<T>)
+where
+    T: From<CommandEvent>
+        + From<Query>
+        + From<Quit>
+        + From<Attach>
+        + From<Detach>
+        + From<Event>
+        + Send
+        + 'static,
+{
+    let path = socket_path();
+    if let Err(e) = ensure_socket_dir() {
+        log::error!("cannot create socket dir for {}: {e}", path.display());
+    }
+    // A stale socket file is cleared; a live one belongs to another game and is left alone.
+    let listener = if UnixStream::connect(&path).is_ok() {
+        log::error!("another game is already listening on {}", path.display());
+        None
+    } else {
+        let _ = fs::remove_file(&path);
+        match UnixListener::bind(&path) {
+            Ok(listener) => {
+                log::info!("listening on {}", path.display());
+                BOUND.store(true, Ordering::Relaxed);
+                let _ = fs::remove_file(quit_marker_path(&socket_path()));
+                Some(listener)
+            }
+            Err(e) => {
+                log::error!("cannot bind {}: {e}", path.display());
+                None
+            }
+        }
+    };
     thread::spawn(move || {
         if let Some(listener) = listener {
-            for stream in listener.incoming() {
-                if let Ok(stream) = stream {
-                    handle_stream(stream, &tx);
-                }
+            for stream in listener.incoming().flatten() {
+                handle_stream(stream, &tx);
             }
         }
     });
 }
 
-fn handle_stream(stream: UnixStream, tx: &mpsc::Sender<CommandEvent>) {
-    let reader = BufReader::new(stream);
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if let Some(ev) = parse_command_line(&line) {
-                let _ = tx.send(ev);
-            }
-        }
+// Quitting on purpose, rather than being killed, tells `waitris guard` to stay down.
+pub fn mark_quit() {
+    if BOUND.load(Ordering::Relaxed) {
+        let _ = fs::write(quit_marker_path(&socket_path()), "");
     }
 }
 
-fn parse_command_line(line: &str) -> Option<CommandEvent> {
-    let line = line.trim();
-    if let Some(rest) = line.strip_prefix("START ") {
-        let mut parts = rest.splitn(2, ' ');
-        let id_str = parts.next()?;
-        let cmd = parts.next().unwrap_or("").trim();
-        let id = id_str.parse().ok()?;
-        return Some(CommandEvent::Start {
-            id,
-            command: cmd.to_string(),
-        });
+// Only the game that bound the socket removes it.
+pub fn remove_socket() {
+    if BOUND.load(Ordering::Relaxed) {
+        let _ = fs::remove_file(socket_path());
     }
-    if let Some(rest) = line.strip_prefix("END ") {
-        let mut parts = rest.split_whitespace();
-        let id_str = parts.next()?;
-        let code_str = parts.next().unwrap_or("0");
-        let id = id_str.parse().ok()?;
-        let exit_code = code_str.parse().unwrap_or(0);
-        return Some(CommandEvent::End { id, _exit_code: exit_code });
+}
+
+fn handle_stream<T>(stream: UnixStream, tx: &mpsc::Sender<T>)
+where
+    T: From<CommandEvent>
+        + From<Query>
+        + From<Quit>
+        + From<Attach>
+        + From<Detach>
+        + From<Event>
+        + Send
+        + 'static,
+{
+    let mut writer = stream.try_clone().ok();
+    let mut lines = BufReader::new(stream).lines();
+    while let Some(line) = lines.next() {
+        let Ok(line) = line else {
+            log::warn!("socket read failed; dropping connection");
+            break;
+        };
+        if line.trim() == "QUIT" {
+            let _ = tx.send(Quit.into());
+            break;
+        }
+        // An attached UI holds its connection open, so its input is read on a thread of its
+        // own and the listener goes back to the hook's events.
+        if let Some(size) = line.trim().strip_prefix("ATTACH ") {
+            let mut dims = size.split_whitespace().map(|n| n.parse::<u16>().ok());
+            let (Some(Some(width)), Some(Some(height))) = (dims.next(), dims.next()) else {
+                log::warn!("bad ATTACH line {line:?}");
+                break;
+            };
+            let Some(stream) = writer.take() else {
+                break;
+            };
+            let id = NEXT_ATTACH.fetch_add(1, Ordering::Relaxed);
+            let _ = tx.send(Attach { id, stream, width, height }.into());
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for line in lines.map_while(Result::ok) {
+                    let Some(json) = line.strip_prefix("EVENT ") else {
+                        continue;
+                    };
+                    match serde_json::from_str::<Event>(json) {
+                        Ok(ev) => {
+                            if tx.send(ev.into()).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => log::warn!("bad EVENT line: {e}"),
+                    }
+                }
+                let _ = tx.send(Detach { id }.into());
+            });
+            return;
+        }
+        if let Some(subject) = line.trim().strip_prefix("QUERY")
+            && matches!(subject.trim(), "" | "GAME")
+        {
+            let game = subject.trim() == "GAME";
+            let (reply, answer) = mpsc::channel();
+            let _ = tx.send(Query { game, reply }.into());
+            if let Ok(json) = answer.recv_timeout(QUERY_TIMEOUT)
+                && let Some(writer) = writer.as_mut()
+            {
+                let _ = writeln!(writer, "{json}");
+            }
+            continue;
+        }
+        match parse_command_line(&line) {
+            Some(ev) => {
+                log::debug!("event {ev:?}");
+                let _ = tx.send(ev.into());
+            }
+            None => log::warn!("unparsed socket line {line:?}"),
+        }
     }
-    None
 }
@@ -0,0 +1,175 @@
+use std::fs;
+use std::io::{self, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::paths::{ensure_socket_dir, watch_socket_path};
+
+// A spectator that stops reading for this long is let go.
+const WATCH_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+// Frames queued for one spectator; one that falls further behind than this is let go.
+const WATCH_BACKLOG: usize = 4;
+// A failed accept (out of file descriptors, say) tends to fail again straight away.
+const ACCEPT_RETRY: Duration = Duration::from_secs(1);
+
+// A read-only viewer that just connected to the watch socket.
+pub struct Spectator {
+    stream: Box<dyn Write + Send>,
+}
+
+// Read-only viewers (`stack-game --watch`): every frame the game draws goes to each of them as
+// one line of JSON, the same the snapshot holds. STACK_WATCH=1 opens a socket beside the game's
+// own; STACK_WATCH=<host:port> listens on TCP instead, for a viewer on another machine. Nothing
+// they send is read.
+#[derive(Default)]
+pub struct Spectators {
+    // Each spectator is written to from a thread of its own, so a slow one never holds up the
+    // game.
+    clients: Vec<SyncSender<Arc<str>>>,
+    // The watch socket this game bound, removed again when it goes.
+    bound: Option<PathBuf>,
+}
+
+impl Spectators {
+    // Newcomers arrive through `tx`, so the loop wakes up to draw them a first frame.
+    pub fn from_env<T>(tx: mpsc::Sender<T>) -> Self
+    where
+        T: From<Spectator> + Send + 'static,
+    {
+        let setting = std::env::var("STACK_WATCH").unwrap_or_default();
+        match setting.trim() {
+            "" | "0" => Self::default(),
+            "1" => {
+                let bound = listen_unix().map(|(listener, path)| {
+                    let accept = move || {
+                        let (stream, _) = listener.accept()?;
+                        stream.set_write_timeout(Some(WATCH_WRITE_TIMEOUT))?;
+                        Ok(Box::new(stream) as Box<dyn Write + Send>)
+                    };
+                    spawn_acceptor(accept, tx);
+                    path
+                });
+                Self { clients: Vec::new(), bound }
+            }
+            addr => {
+                match TcpListener::bind(addr) {
+                    Ok(listener) => {
+                        log::info!("spectators on tcp {addr}");
+                        let accept = move || {
+                            let (stream, _) = listener.accept()?;
+                            stream.set_write_timeout(Some(WATCH_WRITE_TIMEOUT))?;
+                            Ok(Box::new(stream) as Box<dyn Write + Send>)
+                        };
+                        spawn_acceptor(accept, tx);
+                    }
+                    Err(e) => log::error!("STACK_WATCH: cannot listen on {addr}: {e}"),
+                }
+                Self::default()
+            }
+        }
+    }
+
+    pub fn join(&mut self, spectator: Spectator) {
+        log::info!("spectator joined");
+        let (frames, pending) = mpsc::sync_channel::<Arc<str>>(WATCH_BACKLOG);
+        let mut stream = spectator.stream;
+        thread::spawn(move || {
+            for line in pending {
+                if stream.write_all(line.as_bytes()).and_then(|()| stream.flush()).is_err() {
+                    log::info!("spectator left");
+                    break;
+                }
+            }
+        });
+        self.clients.push(frames);
+    }
+
+    // `frame` is only built if anyone is watching.
+    pub fn broadcast(&mut self, frame: impl FnOnce() -> String) {
+        if self.clients.is_empty() {
+            return;
+        }
+        let mut line = frame();
+        line.push('\n');
+        let line: Arc<str> = line.into();
+        self.clients.retain(|client| match client.try_send(Arc::clone(&line)) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                log::info!("spectator fell behind; dropping it");
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+}
+
+impl Drop for Spectators {
+    fn drop(&mut self) {
+        if let Some(path) = &self.bound {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn spawn_acceptor<T, F>(mut accept: F, tx: mpsc::Sender<T>)
+where
+    T: From<Spectator> + Send + 'static,
+    F: FnMut() -> io::Result<Box<dyn Write + Send>> + Send + 'static,
+{
+    thread::spawn(move || {
+        // Logged once per run of failures, not once a second.
+        let mut failing = false;
+        loop {
+            match accept() {
+                Ok(stream) => {
+                    failing = false;
+                    if tx.send(Spectator { stream }.into()).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    if !std::mem::replace(&mut failing, true) {
+                        log::warn!("watch accept failed: {e}");
+                    }
+                    thread::sleep(ACCEPT_RETRY);
+                }
+            }
+        }
+    });
+}
+
+// As with the game's socket, a stale file is cleared and a live one left to its game.
+fn listen_unix() -> Option<(UnixListener, PathBuf)> {
+    let path = watch_socket_path();
+    if let Err(e) = ensure_socket_dir() {
+        log::error!("cannot create socket dir for {}: {e}", path.display());
+    }
+    if UnixStream::connect(&path).is_ok() {
+        log::error!("another game is already watched on {}", path.display());
+        return None;
+    }
+    let _ = fs::remove_file(&path);
+    match UnixListener::bind(&path) {
+        Ok(listener) => {
+            log::info!("spectators on {}", path.display());
+            Some((listener, path))
+        }
+        Err(e) => {
+            log::error!("cannot bind {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+// The viewer's end: the local game's watch socket, or `host:port` for one on TCP.
+pub fn connect_spectator(addr: Option<&str>) -> io::Result<Box<dyn io::Read + Send>> {
+    Ok(match addr {
+        Some(addr) => Box::new(TcpStream::connect(addr)?),
+        None => Box::new(UnixStream::connect(watch_socket_path())?),
+    })
+}
@@ -0,0 +1,242 @@
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::paths;
+use crate::Error;
+
+use super::hook::hook_path;
+use super::screen::{attach_screen, screen_available};
+use super::tmux::{attach_tmux, current_session_name, session_socket, tmux_format, tmux_game_pane};
+use super::{game_binary_path, OnExit, Options};
+
+pub fn quit_session() -> ExitCode {
+    if let Ok(session) = env::var("STY")
+        && env::var("TMUX").is_err()
+    {
+        return match Command::new("screen")
+            .args(["-S", &session, "-X", "quit"])
+            .status()
+        {
+            Ok(s) if s.success() => ExitCode::SUCCESS,
+            _ => ExitCode::from(1),
+        };
+    }
+    if env::var("TMUX").is_err() {
+        eprintln!("waitris quit must be run inside tmux or screen");
+        return ExitCode::from(1);
+    }
+    let session = match current_session_name() {
+        Ok(s) if !s.is_empty() => s,
+        _ => return ExitCode::from(1),
+    };
+    let status = Command::new("tmux")
+        .args(["kill-session", "-t", &session])
+        .status();
+    match status {
+        Ok(s) if s.success() => ExitCode::SUCCESS,
+        Ok(s) => ExitCode::from(s.code().unwrap_or(1) as u8),
+        Err(_) => ExitCode::from(1),
+    }
+}
+
+// This shell's game socket: its tmux session's own when the game was started with
+// --per-session, else the shared one.
+fn game_socket() -> PathBuf {
+    match session_socket() {
+        Some((_, socket)) => socket,
+        None => paths::socket_path(),
+    }
+}
+
+// Someone is listening on the game socket.
+pub(super) fn game_running() -> bool {
+    UnixStream::connect(game_socket()).is_ok()
+}
+
+pub fn attach_game() -> ExitCode {
+    if !game_running() {
+        eprintln!("no game is running; start one with `waitris`");
+        return ExitCode::from(1);
+    }
+    let result = match tmux_game_pane() {
+        Some((session, pane)) => attach_tmux(&session, &pane),
+        None if screen_available() => attach_screen(),
+        None => Err(Error::Tmux(
+            "can't find the pane the game is running in".to_string(),
+        )),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("waitris attach: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+// Ask the game to quit, so it saves and closes its own pane; one that hasn't let go of the
+// socket after a couple of seconds has its pane killed instead.
+pub fn stop_game() -> ExitCode {
+    let Ok(mut stream) = UnixStream::connect(game_socket()) else {
+        eprintln!("no game is running");
+        return ExitCode::from(1);
+    };
+    let _ = writeln!(stream, "QUIT");
+    drop(stream);
+    for _ in 0..20 {
+        if !game_running() {
+            return ExitCode::SUCCESS;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    match tmux_game_pane() {
+        Some((_, pane)) => {
+            let _ = Command::new("tmux")
+                .args(["kill-pane", "-t", &pane])
+                .status();
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("the game didn't quit and isn't in a tmux pane");
+            ExitCode::from(1)
+        }
+    }
+}
+
+// The game's QUERY reply: the session summary, with the game on screen under "current".
+pub(super) fn query_game() -> Option<serde_json::Value> {
+    let mut stream = UnixStream::connect(game_socket()).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(3))).ok()?;
+    writeln!(stream, "QUERY").ok()?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).ok()?;
+    serde_json::from_str(&reply).ok()
+}
+
+// Whether a game is running, where, and how it's doing. Exits 1 when there's none.
+pub fn game_status() -> ExitCode {
+    let socket = game_socket();
+    if !game_running() {
+        println!("not running");
+        return ExitCode::from(1);
+    }
+    let place = match tmux_game_pane() {
+        Some((_, pane)) => {
+            let target = tmux_format(&pane, "#{session_name}:#{window_index}.#{pane_index}");
+            format!("in tmux pane {}", target.unwrap_or(pane))
+        }
+        None => "outside tmux".to_string(),
+    };
+    println!("running {place}, listening on {}", socket.display());
+    if let Some(summary) = query_game() {
+        let current = &summary["current"];
+        println!(
+            "score {}, {} lines, {} commands running",
+            current["score"], current["lines"], current["running"]
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+// How often the guard checks on the game, and how many times a minute it will bring it back
+// before deciding the game can't stay up.
+const GUARD_INTERVAL: Duration = Duration::from_secs(2);
+const GUARD_MAX_RESPAWNS: usize = 3;
+
+// The guard: while the hook is installed, bring the game back in the same window if its
+// process dies or its pane is closed, so commands don't go to a socket nobody reads. A game
+// quit on purpose (q, `waitris stop`) leaves a marker and is let go.
+pub fn guard_game(opts: &Options) -> ExitCode {
+    // Give a game that's just been launched time to bind its socket.
+    let mut last_seen = None;
+    for _ in 0..10 {
+        if let Some((_, pane)) = tmux_game_pane().filter(|_| game_running()) {
+            last_seen = tmux_format(&pane, "#{window_id}").map(|window| (pane, window));
+            break;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    let Some((mut pane, mut window)) = last_seen else {
+        eprintln!("waitris guard: no game running in a tmux pane");
+        return ExitCode::from(1);
+    };
+    let mut respawns: Vec<Instant> = Vec::new();
+    loop {
+        thread::sleep(GUARD_INTERVAL);
+        if game_running() {
+            if let Some((_, seen)) = tmux_game_pane()
+                && let Some(seen_window) = tmux_format(&seen, "#{window_id}")
+            {
+                (pane, window) = (seen, seen_window);
+            }
+            continue;
+        }
+        if paths::quit_marker_path(&game_socket()).exists()
+            || !hook_path().is_ok_and(|p| p.exists())
+        {
+            return ExitCode::SUCCESS;
+        }
+        respawns.retain(|t| t.elapsed() < Duration::from_secs(60));
+        if respawns.len() >= GUARD_MAX_RESPAWNS {
+            eprintln!("waitris guard: the game keeps dying; giving up");
+            return ExitCode::from(1);
+        }
+        match respawn_game(opts, &pane, &window) {
+            Ok(new_pane) => pane = new_pane,
+            Err(e) => {
+                eprintln!("waitris guard: {e}");
+                return ExitCode::from(1);
+            }
+        }
+        respawns.push(Instant::now());
+        // Wait for it to bind before judging it again.
+        thread::sleep(GUARD_INTERVAL);
+    }
+}
+
+// A pane kept after the game died (--keep-pane) is reused; otherwise the game gets a new split
+// in the window it was in.
+fn respawn_game(opts: &Options, pane: &str, window: &str) -> Result<String, Error> {
+    let split = opts.split;
+    let game = opts.game_command(&game_binary_path(opts)?)?;
+    if tmux_format(pane, "#{pane_dead}").as_deref() == Some("1") {
+        let status = Command::new("tmux")
+            .args(["respawn-pane", "-k", "-t", pane, "env", "STACK_MANAGED=1"])
+            .arg(OnExit::KeepPane.env())
+            .arg(format!("STACK_SOCK={}", game_socket().display()))
+            .args(&game)
+            .status()
+            .map_err(|e| Error::Tmux(format!("tmux respawn-pane failed: {e}")))?;
+        return if status.success() {
+            Ok(pane.to_string())
+        } else {
+            Err(Error::Tmux(format!(
+                "tmux respawn-pane failed with status {status}"
+            )))
+        };
+    }
+    let out = Command::new("tmux")
+        .arg("split-window")
+        .args(split.tmux_flags())
+        .args(["-d", "-P", "-F", "#{pane_id}", "-t", window, "-l"])
+        .arg(split.pane_size().to_string())
+        .args(["env", "STACK_MANAGED=1"])
+        .arg(OnExit::KillPane.env())
+        .arg(format!("STACK_SOCK={}", game_socket().display()))
+        .args(&game)
+        .output()
+        .map_err(|e| Error::Tmux(format!("tmux split failed: {e}")))?;
+    if !out.status.success() {
+        return Err(Error::Tmux("the game's window is gone".to_string()));
+    }
+    let new_pane = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    let _ = Command::new("tmux")
+        .args(["select-pane", "-t", &new_pane, "-T", "waitris"])
+        .status();
+    Ok(new_pane)
+}
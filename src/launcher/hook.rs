@@ -0,0 +1,177 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::paths;
+use crate::Error;
+
+const HOOK_SOURCE: &str = include_str!("../../scripts/stack-hook.sh");
+
+// The shells the hook speaks, and the rc file each reads for an interactive shell.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl Shell {
+    const ALL: [Shell; 2] = [Shell::Bash, Shell::Zsh];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            _ => None,
+        }
+    }
+
+    fn rc_name(self) -> &'static str {
+        match self {
+            Shell::Bash => ".bashrc",
+            Shell::Zsh => ".zshrc",
+        }
+    }
+
+    // Your login shell from $SHELL. Anything else gets every supported shell whose rc file is
+    // already there, so the launcher can still set things up from, say, a fish session.
+    fn detect() -> Result<Vec<Self>, Error> {
+        let login = env::var("SHELL").unwrap_or_default();
+        let name = login.rsplit('/').next().unwrap_or_default();
+        if let Some(shell) = Shell::parse(name) {
+            return Ok(vec![shell]);
+        }
+        let present: Vec<Shell> = Shell::ALL
+            .into_iter()
+            .filter(|s| rc_path(s.rc_name()).is_ok_and(|p| p.exists()))
+            .collect();
+        if present.is_empty() {
+            let name = if name.is_empty() { "unset" } else { name };
+            return Err(Error::Config(format!(
+                "the hook works in bash and zsh, and $SHELL is {name}; run `waitris install-hook bash` or `waitris install-hook zsh` to pick one"
+            )));
+        }
+        Ok(present)
+    }
+}
+
+// Writes the hook and sources it from your shell's rc file, behind a check that it's still
+// there so removing the config dir doesn't break new shells. Saying so only when the rc file
+// changes keeps the launcher, which runs this every time, quiet.
+pub fn install_hook(shell: Option<Shell>) -> ExitCode {
+    let hook_path = match hook_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("waitris install-hook: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    let shells = match shell.map_or_else(Shell::detect, |s| Ok(vec![s])) {
+        Ok(shells) => shells,
+        Err(e) => {
+            eprintln!("waitris install-hook: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(hook_path.parent().unwrap_or_else(|| Path::new("/"))) {
+        eprintln!("waitris install-hook: {e}");
+        return ExitCode::from(1);
+    }
+    if let Err(e) = fs::write(&hook_path, HOOK_SOURCE) {
+        eprintln!("waitris install-hook: {e}");
+        return ExitCode::from(1);
+    }
+
+    let [line, legacy] = source_lines(&hook_path);
+    for shell in shells {
+        let rc_name = shell.rc_name();
+        // Older installs added a bare `source` line; it's swapped for the guarded one.
+        let upgraded = remove_rc_line(rc_name, &legacy);
+        match upgraded.and_then(|_| ensure_rc_line(rc_name, &line)) {
+            Ok(true) => println!(
+                "waitris: added the hook to ~/{rc_name}; it's active in new shells, or run `source ~/{rc_name}`"
+            ),
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("waitris install-hook: ~/{rc_name}: {e}");
+                return ExitCode::from(1);
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+// Removed from every supported shell's rc file, whichever one it was added to.
+pub fn uninstall_hook() -> ExitCode {
+    let hook_path = match hook_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("waitris uninstall-hook: {e}");
+            return ExitCode::from(1);
+        }
+    };
+
+    for line in source_lines(&hook_path) {
+        for shell in Shell::ALL {
+            let _ = remove_rc_line(shell.rc_name(), &line);
+        }
+    }
+    let _ = fs::remove_file(&hook_path);
+
+    ExitCode::SUCCESS
+}
+
+// The rc line install-hook writes, and the bare one older builds wrote.
+fn source_lines(hook_path: &Path) -> [String; 2] {
+    let hook = hook_path.display();
+    [
+        format!("[ -f \"{hook}\" ] && . \"{hook}\"  # waitris"),
+        format!("source {hook}"),
+    ]
+}
+
+pub(super) fn hook_path() -> Result<PathBuf, Error> {
+    let dir = paths::config_dir().ok_or_else(|| Error::Config("HOME not set".to_string()))?;
+    Ok(dir.join("stack-hook.sh"))
+}
+
+// Whether the line had to be added.
+fn ensure_rc_line(rc_name: &str, line: &str) -> Result<bool, Error> {
+    let path = rc_path(rc_name)?;
+    let mut contents = fs::read_to_string(&path).unwrap_or_default();
+    if contents.lines().any(|l| l.trim() == line) {
+        return Ok(false);
+    }
+    if !contents.ends_with('\n') && !contents.is_empty() {
+        contents.push('\n');
+    }
+    contents.push_str(line);
+    contents.push('\n');
+    fs::write(&path, contents).map_err(|e| Error::Config(e.to_string()))?;
+    Ok(true)
+}
+
+fn remove_rc_line(rc_name: &str, line: &str) -> Result<(), Error> {
+    let path = rc_path(rc_name)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return Ok(()),
+    };
+    if !contents.lines().any(|l| l.trim() == line) {
+        return Ok(());
+    }
+    let filtered: Vec<&str> = contents.lines().filter(|l| l.trim() != line).collect();
+    let mut new_contents = filtered.join("\n");
+    if !new_contents.is_empty() {
+        new_contents.push('\n');
+    }
+    fs::write(&path, new_contents).map_err(|e| Error::Config(e.to_string()))?;
+    Ok(())
+}
+
+fn rc_path(rc_name: &str) -> Result<PathBuf, Error> {
+    let home = env::var("HOME").map_err(|_| Error::Config("HOME not set".to_string()))?;
+    Ok(Path::new(&home).join(rc_name))
+}
@@ -0,0 +1,469 @@
+// The waitris launcher: puts the game beside your shell in tmux or screen, and talks to it once
+// it's running. The waitris binary parses its command line and calls in here.
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use crate::Error;
+
+mod control;
+mod hook;
+mod screen;
+mod tmux;
+
+pub use control::{attach_game, game_status, guard_game, quit_session, stop_game};
+pub use hook::{install_hook, uninstall_hook, Shell};
+pub use tmux::{publish_status_line, toggle_game};
+
+use control::game_running;
+use screen::{run_inside_screen, run_new_screen_session, screen_available};
+use tmux::{
+    current_session_name, run_inside_tmux, run_new_tmux_session, session_socket_path,
+    tmux_available,
+};
+
+// Where the game pane goes, relative to the shell.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Split {
+    #[default]
+    Right,
+    Left,
+    Bottom,
+}
+
+impl Split {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "right" => Some(Split::Right),
+            "left" => Some(Split::Left),
+            "bottom" => Some(Split::Bottom),
+            _ => None,
+        }
+    }
+
+    fn tmux_flags(self) -> &'static [&'static str] {
+        match self {
+            Split::Right => &["-h"],
+            Split::Left => &["-h", "-b"],
+            Split::Bottom => &["-v"],
+        }
+    }
+
+    // Back from the game pane to the shell.
+    fn towards_shell(self) -> &'static str {
+        match self {
+            Split::Right => "-L",
+            Split::Left => "-R",
+            Split::Bottom => "-U",
+        }
+    }
+
+    // resize-pane's flag for the dimension pane_size measures.
+    fn resize_flag(self) -> &'static str {
+        match self {
+            Split::Bottom => "-y",
+            _ => "-x",
+        }
+    }
+
+    // Columns for a side split, rows for a bottom one; the defaults are the game's minimum.
+    fn pane_size(self) -> u16 {
+        let (var, default) = match self {
+            Split::Bottom => ("STACK_PANE_H", 24),
+            _ => ("STACK_PANE_W", 24),
+        };
+        env::var(var)
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok())
+            .unwrap_or(default)
+    }
+}
+
+// What happens when the game quits, handed to it as STACK_ON_EXIT.
+#[derive(Clone, Copy, PartialEq)]
+pub enum OnExit {
+    KillSession,
+    KillPane,
+    KeepPane,
+    Detach,
+}
+
+impl OnExit {
+    fn env(self) -> String {
+        let name = match self {
+            OnExit::KillSession => "kill-session",
+            OnExit::KillPane => "kill-pane",
+            OnExit::KeepPane => "keep-pane",
+            OnExit::Detach => "detach",
+        };
+        format!("STACK_ON_EXIT={name}")
+    }
+}
+
+// What the launcher was asked for on its command line.
+#[derive(Default)]
+pub struct Options {
+    pub split: Split,
+    pub backend: Option<Backend>,
+    // None leaves it to where the game lands; see Backend::launch.
+    pub on_exit: Option<OnExit>,
+    pub border: Option<String>,
+    pub status_line: bool,
+    pub guard: bool,
+    // A socket of the tmux session's own, so each session can run its own game.
+    pub per_session: bool,
+    // Passed on to the game: --profile as an argument, --config's settings as its environment.
+    pub profile: Option<String>,
+    pub config: Option<PathBuf>,
+    // Overrides WAITRIS_GAME_BIN; see game_binary_path.
+    pub game_bin: Option<String>,
+    // Full screen in this terminal, no multiplexer.
+    pub no_tmux: bool,
+}
+
+// The speed profiles the game's --profile takes.
+pub const PROFILES: [&str; 3] = ["casual", "normal", "hardcore"];
+
+impl Options {
+    // The game's command line as it follows `env` in a pane: the --config settings, the
+    // binary, then --profile.
+    fn game_command(&self, game_cmd: &str) -> Result<Vec<String>, Error> {
+        let mut words = match &self.config {
+            Some(path) => read_config(path)?,
+            None => Vec::new(),
+        };
+        words.push(game_cmd.to_string());
+        if let Some(profile) = &self.profile {
+            words.extend(["--profile".to_string(), profile.clone()]);
+        }
+        Ok(words)
+    }
+}
+
+// A config file holds the game's STACK_* settings, one `NAME=value` per line; blank lines and
+// `#` comments are skipped.
+fn read_config(path: &Path) -> Result<Vec<String>, Error> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("cannot read {}: {e}", path.display())))?;
+    let mut settings = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((name, value)) if name.trim().starts_with("STACK_") => {
+                settings.push(format!("{}={}", name.trim(), value.trim()))
+            }
+            _ => {
+                return Err(Error::Config(format!(
+                    "{}:{}: expected STACK_<NAME>=<value>",
+                    path.display(),
+                    n + 1
+                )));
+            }
+        }
+    }
+    Ok(settings)
+}
+
+// The terminal multiplexer that puts the shell and the game side by side.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Backend {
+    Tmux,
+    Screen,
+}
+
+impl Backend {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "tmux" => Some(Backend::Tmux),
+            "screen" => Some(Backend::Screen),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Backend::Tmux => "tmux",
+            Backend::Screen => "screen",
+        }
+    }
+
+    // The one we're already running in, else tmux, else screen for servers that only have it.
+    fn detect() -> Option<Self> {
+        [Backend::Tmux, Backend::Screen]
+            .into_iter()
+            .find(|b| b.inside())
+            .or_else(|| {
+                [Backend::Tmux, Backend::Screen]
+                    .into_iter()
+                    .find(|b| b.available())
+            })
+    }
+
+    fn inside(self) -> bool {
+        match self {
+            Backend::Tmux => env::var("TMUX").is_ok(),
+            Backend::Screen => env::var("STY").is_ok(),
+        }
+    }
+
+    fn available(self) -> bool {
+        match self {
+            Backend::Tmux => tmux_available(),
+            Backend::Screen => screen_available(),
+        }
+    }
+
+    // A session made for the game goes away with it; a split in your own session only closes
+    // its pane.
+    fn launch(self, opts: &Options, game: &[String]) -> Result<(), Error> {
+        let inside = self.inside();
+        let on_exit = opts.on_exit.unwrap_or(if inside {
+            OnExit::KillPane
+        } else {
+            OnExit::KillSession
+        });
+        match (self, inside) {
+            (Backend::Tmux, true) => run_inside_tmux(opts, on_exit, game),
+            (Backend::Tmux, false) => run_new_tmux_session(opts, on_exit, game),
+            (Backend::Screen, true) => run_inside_screen(opts.split, on_exit, game),
+            (Backend::Screen, false) => run_new_screen_session(opts.split, on_exit, game),
+        }
+    }
+}
+
+pub fn run_launcher(opts: &Options) -> ExitCode {
+    // Reuse a running game rather than stacking a second, deaf one beside it: make sure the
+    // hook is in place, then focus it. --per-session only reuses this session's own game, and
+    // outside tmux always makes a new session.
+    let running = if !opts.per_session {
+        game_running()
+    } else if env::var("TMUX").is_ok() {
+        current_session_name().is_ok_and(|s| UnixStream::connect(session_socket_path(&s)).is_ok())
+    } else {
+        false
+    };
+    if running {
+        if install_hook(None) != ExitCode::SUCCESS {
+            return ExitCode::from(1);
+        }
+        return attach_game();
+    }
+
+    if opts.no_tmux {
+        return run_standalone(opts);
+    }
+
+    let backend = match opts.backend.or_else(Backend::detect) {
+        Some(b) if b.available() => b,
+        Some(b) => {
+            eprintln!("{} not found on PATH.", b.name());
+            return ExitCode::from(1);
+        }
+        None => {
+            eprintln!(
+                "tmux not found on PATH. Please install tmux (or GNU screen) to use waitris."
+            );
+            return ExitCode::from(1);
+        }
+    };
+
+    let game = match game_binary_path(opts) {
+        Ok(p) => opts.game_command(&p),
+        Err(e) => {
+            eprintln!("cannot find stack-game binary: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+    let result = game.and_then(|game| backend.launch(opts, &game));
+    if let Err(err) = result {
+        eprintln!("waitris launcher error: {err}");
+        ExitCode::from(err.exit_code())
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// The game takes over this terminal; commands typed in any other shell with the hook still
+// reach it, since the hook finds the same socket.
+fn run_standalone(opts: &Options) -> ExitCode {
+    if install_hook(None) != ExitCode::SUCCESS {
+        return ExitCode::from(1);
+    }
+    let game = match game_binary_path(opts) {
+        Ok(p) => opts.game_command(&p),
+        Err(e) => {
+            eprintln!("cannot find stack-game binary: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+    let game = match game {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("waitris: {e}");
+            return ExitCode::from(e.exit_code());
+        }
+    };
+    let err = Command::new("env").args(game).exec();
+    eprintln!("cannot run stack-game: {err}");
+    ExitCode::from(1)
+}
+
+// The mini mode and the guard run under the tmux server, so they outlive the launcher and its
+// terminal.
+fn start_helpers(opts: &Options) {
+    let Ok(exe) = env::current_exe() else {
+        return;
+    };
+    let exe = shell_quote(&exe.display().to_string());
+    let split = match opts.split {
+        Split::Right => "right",
+        Split::Left => "left",
+        Split::Bottom => "bottom",
+    };
+    // The guard starts the game the way the launcher did.
+    let mut guard = format!("--split {split}");
+    if let Some(profile) = &opts.profile {
+        guard.push_str(&format!(" --profile {profile}"));
+    }
+    if let Some(config) = &opts.config {
+        guard.push_str(&format!(
+            " --config {}",
+            shell_quote(&config.display().to_string())
+        ));
+    }
+    if let Ok(game_bin) = game_binary_path(opts) {
+        guard.push_str(&format!(" --game-bin {}", shell_quote(&game_bin)));
+    }
+    let helpers = [
+        (opts.status_line, "mini".to_string()),
+        (opts.guard, format!("{guard} guard")),
+    ];
+    for (wanted, args) in helpers {
+        if wanted {
+            let _ = Command::new("tmux")
+                .args(["run-shell", "-b", &format!("{exe} {args}")])
+                .status();
+        }
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+// A read-only view of a game started with STACK_WATCH, in this terminal: the game binary's
+// --watch, on the local game or one listening at host:port.
+pub fn watch_game(opts: &Options, addr: Option<String>) -> ExitCode {
+    let game_cmd = match game_binary_path(opts) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("cannot find stack-game binary: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    match Command::new(game_cmd).arg("--watch").args(addr).status() {
+        Ok(s) if s.success() => ExitCode::SUCCESS,
+        Ok(s) => ExitCode::from(s.code().unwrap_or(1) as u8),
+        Err(e) => {
+            eprintln!("cannot run stack-game: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+// The game binary owns the stats store, so just hand over to it.
+pub fn show_stats(opts: &Options, args: Vec<String>) -> ExitCode {
+    let game_args = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => vec!["--stats".to_string()],
+        ["summary"] => vec!["--stats-summary".to_string(), "week".to_string()],
+        ["summary", "--period", period] => vec!["--stats-summary".to_string(), period.to_string()],
+        ["export", file] => vec!["--stats-export".to_string(), file.to_string()],
+        ["import", file] => vec!["--stats-import".to_string(), file.to_string()],
+        _ => {
+            eprintln!(
+                "usage: waitris stats [summary --period day|week|month | export <file> | import <file>]"
+            );
+            return ExitCode::from(2);
+        }
+    };
+    let game_cmd = match game_binary_path(opts) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("cannot find stack-game binary: {e}");
+            return ExitCode::from(1);
+        }
+    };
+    match Command::new(game_cmd).args(game_args).status() {
+        Ok(s) if s.success() => ExitCode::SUCCESS,
+        Ok(s) => ExitCode::from(s.code().unwrap_or(1) as u8),
+        Err(e) => {
+            eprintln!("cannot run stack-game: {e}");
+            ExitCode::from(1)
+        }
+    }
+}
+
+// --game-bin or $WAITRIS_GAME_BIN when given, and nothing else then. Otherwise the one beside
+// this binary, then PATH, then ~/.cargo/bin, where `cargo install` puts it but which a
+// non-login shell in tmux may not have on PATH.
+fn game_binary_path(opts: &Options) -> Result<String, Error> {
+    let wanted = opts
+        .game_bin
+        .clone()
+        .map(|p| ("--game-bin", p))
+        .or_else(|| {
+            env::var("WAITRIS_GAME_BIN")
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(|p| ("WAITRIS_GAME_BIN", p))
+        });
+    if let Some((source, path)) = wanted {
+        return if is_executable(Path::new(&path)) {
+            Ok(path)
+        } else {
+            Err(Error::Config(format!(
+                "{source} is {path}, which isn't an executable file"
+            )))
+        };
+    }
+
+    let mut tried = Vec::new();
+    if let Some(dir) = env::current_exe().ok().as_deref().and_then(Path::parent) {
+        tried.push(dir.join("stack-game"));
+    }
+    if let Some(path) = env::var_os("PATH") {
+        tried.extend(env::split_paths(&path).map(|dir| dir.join("stack-game")));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        tried.push(
+            Path::new(&home)
+                .join(".cargo")
+                .join("bin")
+                .join("stack-game"),
+        );
+    }
+    if let Some(found) = tried.iter().find(|p| is_executable(p)) {
+        return found
+            .to_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| Error::Config("non-utf8 path to stack-game".to_string()));
+    }
+    let listing: String = tried
+        .iter()
+        .map(|p| format!("\n  {}", p.display()))
+        .collect();
+    Err(Error::Config(format!(
+        "looked in:{listing}\nset WAITRIS_GAME_BIN or pass --game-bin <path> if it's somewhere else"
+    )))
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
@@ -0,0 +1,163 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use crate::paths;
+use crate::Error;
+
+use super::{OnExit, Split};
+
+// Screen regions belong to the display, so the layout is built with -X commands inside a
+// running session and from a generated screenrc for a new one.
+pub(super) fn screen_available() -> bool {
+    // `screen -v` exits non-zero on some versions, so only ask whether it runs.
+    Command::new("screen").arg("-v").output().is_ok()
+}
+
+fn words(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+// Split off a region, run the game in it at its exact size, and focus back on the shell
+// window. A left split keeps the region that was current for the game.
+fn screen_layout(
+    split: Split,
+    game_cmd: &[String],
+    on_exit: OnExit,
+    shell_window: &str,
+) -> Vec<Vec<String>> {
+    let mut game = words(&[
+        "screen",
+        "-t",
+        "waitris",
+        "env",
+        "STACK_MANAGED=1",
+        &on_exit.env(),
+    ]);
+    game.extend_from_slice(game_cmd);
+    let size = split.pane_size().to_string();
+    match split {
+        Split::Right => vec![
+            words(&["split", "-v"]),
+            words(&["focus", "right"]),
+            game,
+            words(&["resize", "-h", &size]),
+            words(&["focus", "left"]),
+        ],
+        Split::Left => vec![
+            words(&["split", "-v"]),
+            game,
+            words(&["resize", "-h", &size]),
+            words(&["focus", "right"]),
+            words(&["select", shell_window]),
+        ],
+        Split::Bottom => vec![
+            words(&["split"]),
+            words(&["focus", "down"]),
+            game,
+            words(&["resize", "-v", &size]),
+            words(&["focus", "up"]),
+        ],
+    }
+}
+
+pub(super) fn run_inside_screen(
+    split: Split,
+    on_exit: OnExit,
+    game: &[String],
+) -> Result<(), Error> {
+    let session = env::var("STY").map_err(|_| Error::Tmux("STY not set".to_string()))?;
+    let window = env::var("WINDOW").unwrap_or_else(|_| "0".to_string());
+    for cmd in screen_layout(split, game, on_exit, &window) {
+        let status = Command::new("screen")
+            .args(["-S", &session, "-X"])
+            .args(&cmd)
+            .status()
+            .map_err(|e| Error::Tmux(format!("screen {} failed: {e}", cmd[0])))?;
+        if !status.success() {
+            return Err(Error::Tmux(format!(
+                "screen {} failed with status {}",
+                cmd[0], status
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn run_new_screen_session(
+    split: Split,
+    on_exit: OnExit,
+    game: &[String],
+) -> Result<(), Error> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
+    let session = format!("waitris-{}", std::process::id());
+    let dir = paths::runtime_dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| Error::Tmux(format!("cannot create {}: {e}", dir.display())))?;
+    let rc_path = dir.join(format!("{session}.screenrc"));
+
+    let mut rc = vec![
+        words(&["startup_message", "off"]),
+        words(&["screen", "-t", "shell", "0", &shell]),
+    ];
+    rc.extend(screen_layout(split, game, on_exit, "0"));
+    let rc: String = rc
+        .iter()
+        .map(|cmd| {
+            let args: Vec<String> = cmd[1..].iter().map(|a| screenrc_quote(a)).collect();
+            format!("{} {}\n", cmd[0], args.join(" "))
+        })
+        .collect();
+    fs::write(&rc_path, rc)
+        .map_err(|e| Error::Tmux(format!("cannot write {}: {e}", rc_path.display())))?;
+
+    let status = Command::new("screen")
+        .args(["-S", &session, "-c"])
+        .arg(&rc_path)
+        .status();
+    let _ = fs::remove_file(&rc_path);
+    status.map_err(|e| Error::Tmux(format!("screen failed: {e}")))?;
+    Ok(())
+}
+
+// Screenrc words are split on spaces and expand $VARS, so each argument is quoted whole.
+fn screenrc_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '\\' | '$') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// The launcher titles the game's window "waitris": select it in this screen session, or
+// reattach to the session the launcher made.
+pub(super) fn attach_screen() -> Result<(), Error> {
+    let status = if let Ok(session) = env::var("STY") {
+        Command::new("screen")
+            .args(["-S", &session, "-X", "select", "waitris"])
+            .status()
+    } else {
+        let out = Command::new("screen")
+            .arg("-ls")
+            .output()
+            .map_err(|e| Error::Tmux(format!("screen -ls failed: {e}")))?;
+        let listing = String::from_utf8_lossy(&out.stdout);
+        let session = listing
+            .lines()
+            .filter_map(|l| l.split_whitespace().next())
+            .find(|name| name.contains(".waitris-"))
+            .ok_or_else(|| Error::Tmux("can't find the pane the game is running in".to_string()))?;
+        Command::new("screen")
+            .args(["-x", session, "-p", "waitris"])
+            .status()
+    };
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(Error::Tmux(format!("screen failed with status {s}"))),
+        Err(e) => Err(Error::Tmux(format!("screen failed: {e}"))),
+    }
+}
@@ -0,0 +1,426 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use crate::paths;
+use crate::Error;
+
+use super::control::query_game;
+use super::{start_helpers, OnExit, Options, Split};
+
+pub(super) fn tmux_available() -> bool {
+    Command::new("tmux")
+        .arg("-V")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+// The window already has its real size here, so the pane gets exactly the cells asked for.
+pub(super) fn run_inside_tmux(
+    opts: &Options,
+    on_exit: OnExit,
+    game: &[String],
+) -> Result<(), Error> {
+    let size = opts.split.pane_size().to_string();
+    let session = current_session_name()?;
+    let socket = publish_socket(&session, opts.per_session);
+    split_game_pane(
+        opts.split,
+        &size,
+        on_exit,
+        opts.border.as_deref(),
+        socket.as_deref(),
+        game,
+    )?;
+    apply_session_options(&session, on_exit, false);
+    let _ = Command::new("tmux")
+        .args(["select-pane", opts.split.towards_shell()])
+        .status();
+    start_helpers(opts);
+    Ok(())
+}
+
+// A detached session starts at tmux's default size and is resized on attach, which would
+// throw off an exact split. So it's created at the size of the terminal we're running in, and
+// the game's pane is snapped back to its exact size whenever the window is resized: on
+// attach when the terminal's size couldn't be read, and when the terminal is resized later.
+pub(super) fn run_new_tmux_session(
+    opts: &Options,
+    on_exit: OnExit,
+    game: &[String],
+) -> Result<(), Error> {
+    let split = opts.split;
+    let client = crossterm::terminal::size().ok();
+    let size = match client {
+        Some(_) => split.pane_size().to_string(),
+        None => "50%".to_string(),
+    };
+    let shell = env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
+    let session = format!("waitris-{}", std::process::id());
+    let mut new_session = Command::new("tmux");
+    new_session.args(["-f", "/dev/null", "new-session", "-d", "-s", &session]);
+    if let Some((cols, rows)) = client {
+        new_session.args(["-x", &cols.to_string(), "-y", &rows.to_string()]);
+    }
+    let status = new_session
+        .arg(&shell)
+        .status()
+        .map_err(|e| Error::Tmux(format!("tmux new-session failed: {e}")))?;
+    if !status.success() {
+        return Err(Error::Tmux(format!(
+            "tmux new-session failed with status {}",
+            status
+        )));
+    }
+    apply_session_options(&session, on_exit, true);
+    let socket = publish_socket(&session, opts.per_session);
+    let pane = split_game_pane(
+        split,
+        &size,
+        on_exit,
+        opts.border.as_deref(),
+        socket.as_deref(),
+        game,
+    )?;
+    let resize = format!(
+        "resize-pane -t {pane} {} {}",
+        split.resize_flag(),
+        split.pane_size()
+    );
+    let _ = Command::new("tmux")
+        .args(["set-hook", "-t", &session, "window-resized", &resize])
+        .status();
+    let _ = Command::new("tmux")
+        .args(["select-pane", split.towards_shell()])
+        .status();
+    start_helpers(opts);
+    let _ = Command::new("tmux")
+        .args(["attach-session", "-t", &session])
+        .status();
+    Ok(())
+}
+
+// The game's pane is titled "waitris" for pane-status lines. Kept panes stay on screen, dead,
+// after the game quits (tmux 3.0 or newer).
+fn split_game_pane(
+    split: Split,
+    size: &str,
+    on_exit: OnExit,
+    border: Option<&str>,
+    socket: Option<&Path>,
+    game: &[String],
+) -> Result<String, Error> {
+    let mut game_env = Vec::new();
+    if let Some(socket) = socket {
+        game_env.push(format!("STACK_SOCK={}", socket.display()));
+    }
+    // Border styles belong to the window, so the game puts back whatever was there before.
+    if border.is_some() {
+        let previous = BORDER_OPTIONS.map(|option| window_option(option).unwrap_or_default());
+        game_env.push(format!("STACK_BORDER_RESTORE={}", previous.join("|")));
+    }
+    let out = Command::new("tmux")
+        .arg("split-window")
+        .args(split.tmux_flags())
+        .args([
+            "-P",
+            "-F",
+            "#{pane_id}",
+            "-l",
+            size,
+            "env",
+            "STACK_MANAGED=1",
+            &on_exit.env(),
+        ])
+        .args(&game_env)
+        .args(game)
+        .output()
+        .map_err(|e| Error::Tmux(format!("tmux split failed: {e}")))?;
+    if !out.status.success() {
+        return Err(Error::Tmux(format!(
+            "tmux split failed with status {}",
+            out.status
+        )));
+    }
+    let pane = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    let _ = Command::new("tmux")
+        .args(["select-pane", "-t", &pane, "-T", "waitris"])
+        .status();
+    if let Some(colour) = border {
+        for option in BORDER_OPTIONS {
+            let _ = Command::new("tmux")
+                .args([
+                    "set-option",
+                    "-w",
+                    "-t",
+                    &pane,
+                    option,
+                    &format!("fg={colour}"),
+                ])
+                .status();
+        }
+    }
+    if on_exit == OnExit::KeepPane {
+        let _ = Command::new("tmux")
+            .args(["set-option", "-p", "-t", &pane, "remain-on-exit", "on"])
+            .status();
+    }
+    Ok(pane)
+}
+
+// --per-session puts the session's own socket in its environment, where the hook and the
+// launcher's subcommands look first; without it a socket left there by an earlier game is
+// cleared, so this session goes back to the shared one.
+fn publish_socket(session: &str, per_session: bool) -> Option<PathBuf> {
+    if !per_session {
+        let _ = Command::new("tmux")
+            .args(["set-environment", "-t", session, "-u", "STACK_SOCK"])
+            .stderr(Stdio::null())
+            .status();
+        return None;
+    }
+    let socket = session_socket_path(session);
+    let _ = Command::new("tmux")
+        .args(["set-environment", "-t", session, "STACK_SOCK"])
+        .arg(&socket)
+        .status();
+    Some(socket)
+}
+
+const BORDER_OPTIONS: [&str; 2] = ["pane-border-style", "pane-active-border-style"];
+
+// The current window's own value for an option, None when it only inherits one.
+fn window_option(option: &str) -> Option<String> {
+    let mut cmd = Command::new("tmux");
+    cmd.args(["show-options", "-w", "-v"]);
+    if let Ok(pane) = env::var("TMUX_PANE") {
+        cmd.args(["-t", &pane]);
+    }
+    let out = cmd.arg(option).output().ok()?;
+    let value = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !value.is_empty()).then_some(value)
+}
+
+// A session the launcher made gets a bare look; one of yours keeps its status line and pane
+// status lines, where the game's pane shows up by its title.
+fn apply_session_options(session: &str, on_exit: OnExit, made_here: bool) {
+    if made_here {
+        let _ = Command::new("tmux")
+            .args(["set", "-t", session, "status", "off"])
+            .status();
+        let _ = Command::new("tmux")
+            .args(["set", "-t", session, "pane-border-status", "off"])
+            .status();
+        let _ = Command::new("tmux")
+            .args(["set", "-t", session, "display-panes-time", "1"])
+            .status();
+    }
+    // Only a session that goes away with the game goes away with the shell too.
+    if on_exit == OnExit::KillSession {
+        let _ = Command::new("tmux")
+            .args(["set-hook", "-t", session, "pane-exited", "kill-session"])
+            .status();
+    }
+    let _ = Command::new("tmux")
+        .args(["bind-key", "-n", "C-Space", "select-pane", "-t", ":.+"])
+        .status();
+}
+
+// A tmux format expanded for a target pane or window, None if tmux can't find it.
+pub(super) fn tmux_format(target: &str, format: &str) -> Option<String> {
+    let out = Command::new("tmux")
+        .args(["display-message", "-p", "-t", target, format])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+// The session and pane id of the tmux pane running this shell's game, if it's in one. A
+// --per-session game is looked for in its own session; the shared one in any session without a
+// socket of its own.
+pub(super) fn tmux_game_pane() -> Option<(String, String)> {
+    let own = session_socket().map(|(session, _)| session);
+    let mut cmd = Command::new("tmux");
+    match &own {
+        Some(session) => cmd.args(["list-panes", "-s", "-t", session]),
+        None => cmd.args(["list-panes", "-a"]),
+    };
+    let out = cmd
+        .args(["-F", "#{session_name}\t#{pane_id}\t#{pane_current_command}"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.split('\t');
+            let (session, pane) = (fields.next()?, fields.next()?);
+            (fields.next()? == "stack-game"
+                && (own.is_some() || socket_in_environment(Some(session)).is_none()))
+            .then(|| (session.to_string(), pane.to_string()))
+        })
+}
+
+// Focus the game's pane, switching this client to its session, or attaching to it from
+// outside tmux.
+pub(super) fn attach_tmux(session: &str, pane: &str) -> Result<(), Error> {
+    let _ = Command::new("tmux")
+        .args(["select-window", "-t", pane])
+        .status();
+    let _ = Command::new("tmux")
+        .args(["select-pane", "-t", pane])
+        .status();
+    let args = if env::var("TMUX").is_ok() {
+        ["switch-client", "-t", session]
+    } else {
+        ["attach-session", "-t", session]
+    };
+    let status = Command::new("tmux")
+        .args(args)
+        .status()
+        .map_err(|e| Error::Tmux(format!("tmux {} failed: {e}", args[0])))?;
+    if !status.success() {
+        return Err(Error::Tmux(format!(
+            "tmux {} failed with status {}",
+            args[0], status
+        )));
+    }
+    Ok(())
+}
+
+pub(super) fn current_session_name() -> Result<String, Error> {
+    let out = Command::new("tmux")
+        .args(["display-message", "-p", "#S"])
+        .output()
+        .map_err(|e| Error::Tmux(e.to_string()))?;
+    if !out.status.success() {
+        return Err(Error::Tmux("tmux display-message failed".to_string()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+// With --per-session each tmux session gets a socket of its own, named after it.
+pub(super) fn session_socket_path(session: &str) -> PathBuf {
+    let name: String = session
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    paths::runtime_dir().join(format!("stack-game-{name}.sock"))
+}
+
+// The current tmux session and the socket in its environment, if it has one.
+pub(super) fn session_socket() -> Option<(String, PathBuf)> {
+    env::var_os("TMUX")?;
+    let socket = socket_in_environment(None)?;
+    Some((current_session_name().ok()?, socket))
+}
+
+fn socket_in_environment(session: Option<&str>) -> Option<PathBuf> {
+    let mut cmd = Command::new("tmux");
+    cmd.arg("show-environment");
+    if let Some(session) = session {
+        cmd.args(["-t", session]);
+    }
+    let out = cmd.arg("STACK_SOCK").stderr(Stdio::null()).output().ok()?;
+    let line = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    let socket = line.strip_prefix("STACK_SOCK=")?;
+    (!socket.is_empty()).then(|| PathBuf::from(socket))
+}
+
+// Hide the game's pane in a window of its own, or bring it back beside this one. The game
+// keeps running either way.
+pub fn toggle_game(split: Split) -> ExitCode {
+    let Ok(here) = env::var("TMUX_PANE") else {
+        eprintln!("waitris toggle must be run inside tmux");
+        return ExitCode::from(1);
+    };
+    let Some((_, pane)) = tmux_game_pane() else {
+        eprintln!("no game is running in a tmux pane");
+        return ExitCode::from(1);
+    };
+    let size = split.pane_size().to_string();
+    let status = if tmux_format(&pane, "#{window_id}") == tmux_format(&here, "#{window_id}") {
+        Command::new("tmux")
+            .args(["break-pane", "-d", "-s", &pane, "-n", "waitris"])
+            .status()
+    } else {
+        Command::new("tmux")
+            .arg("join-pane")
+            .args(split.tmux_flags())
+            .args(["-d", "-l", &size, "-s", &pane, "-t", &here])
+            .status()
+    };
+    match status {
+        Ok(s) if s.success() => ExitCode::SUCCESS,
+        _ => ExitCode::from(1),
+    }
+}
+
+// How often the mini mode asks the game for its score.
+const MINI_INTERVAL: Duration = Duration::from_secs(2);
+
+// The mini mode: while the game's pane is out of sight (another window, a detached session,
+// or zoomed away from), keep the global @waitris_score and @waitris_status options up to date
+// for status-right; blank them while it's on screen. Stops, clearing them, when the game quits.
+pub fn publish_status_line() -> ExitCode {
+    while let Some(summary) = query_game() {
+        let current = &summary["current"];
+        let (score, status) = if game_on_screen() {
+            (String::new(), String::new())
+        } else {
+            let mut status = format!("waitris {} · {}L", current["score"], current["lines"]);
+            if current["game_over"] == true {
+                status.push_str(" · game over");
+            } else if current["running"].as_u64().is_some_and(|n| n > 0) {
+                status.push_str(&format!(" · {} running", current["running"]));
+            }
+            (current["score"].to_string(), status)
+        };
+        set_status_option("@waitris_score", Some(&score));
+        set_status_option("@waitris_status", Some(&status));
+        thread::sleep(MINI_INTERVAL);
+    }
+    set_status_option("@waitris_score", None);
+    set_status_option("@waitris_status", None);
+    ExitCode::SUCCESS
+}
+
+// The game's pane is in the window some client is looking at, and not zoomed away from.
+fn game_on_screen() -> bool {
+    let Some((_, pane)) = tmux_game_pane() else {
+        return false;
+    };
+    let flags = tmux_format(
+        &pane,
+        "#{session_attached} #{window_active} #{window_zoomed_flag} #{pane_active}",
+    )
+    .unwrap_or_default();
+    match flags.split(' ').collect::<Vec<_>>()[..] {
+        [attached, window, zoomed, pane] => {
+            attached != "0" && window == "1" && (zoomed == "0" || pane == "1")
+        }
+        _ => false,
+    }
+}
+
+// Global for the shared game; a --per-session game's score only shows in its own session.
+fn set_status_option(option: &str, value: Option<&str>) {
+    let mut cmd = Command::new("tmux");
+    cmd.arg("set-option");
+    match session_socket() {
+        Some((session, _)) => cmd.args(["-q", "-t", &session]),
+        None => cmd.arg("-gq"),
+    };
+    match value {
+        Some(value) => cmd.args([option, value]),
+        None => cmd.args(["-u", option]),
+    };
+    let _ = cmd.status();
+}
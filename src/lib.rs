@@ -0,0 +1,32 @@
+// The game as a library: the binaries are thin wrappers over it, and other crates can drive a
+// `Game` directly with the same command events the socket delivers.
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod commands;
+pub mod config;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod game;
+pub mod io;
+#[cfg(feature = "launcher")]
+pub mod launcher;
+// Files and directories: wasm32 has none, so a browser build is just the game core.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod logging;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod paths;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod persist;
+pub mod testing;
+#[cfg(feature = "tui")]
+mod ui;
+
+pub use config::{
+    BOMB_CAP, BOARD_H, BOARD_W, CELL_W, CHUNK_SIZE, CRASH_GARBAGE, DANGER_ROWS, LINES_PER_LEVEL,
+    MAX_CELL_W, MAX_HIGH_SCORES, MAX_LONGEST_WAITS, MAX_REPLAY_STEPS, MIN_PANE_HEIGHT,
+    MIN_PANE_WIDTH, PLAY_H, PLAY_W, REPEAT_SECS, RESULT_PIECES, SPRINT_LINES, ULTRA_SECS,
+    VARIETY_THRESH,
+};
+pub use error::Error;
+pub use game::{CommandEvent, Game};
@@ -1,17 +1,104 @@
 use std::error::Error;
 
-mod app;
-mod config;
-mod game;
-mod io;
-mod ui;
-mod commands;
-pub use game::{CommandEvent, Game};
-pub use config::{
-    BOMB_CAP, BOARD_H, BOARD_W, CELL_W, CHUNK_SIZE, MIN_PANE_WIDTH, PLAY_H, PLAY_W, SOCKET_PATH,
-    VARIETY_THRESH,
-};
+use stack_game::{app, game, logging, persist};
 
 fn main() -> Result<(), Box<dyn Error>> {
-    app::run()
+    let mut args = std::env::args().skip(1).peekable();
+    let mut script = None;
+    let mut headless = false;
+    let mut render = false;
+    let mut log_file = None;
+    let mut verbosity = 0u8;
+    let mut stats = false;
+    let mut summary_days = None;
+    let mut export = None;
+    let mut import = None;
+    let mut replay = None;
+    let mut seed = None;
+    let mut profile = None;
+    let mut daemon = false;
+    let mut attach = false;
+    let mut watch = None;
+    let mut autoplay = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--simulate" => script = Some(args.next().ok_or("--simulate needs an events file")?),
+            "--headless" => headless = true,
+            "--render" => render = true,
+            "--stats" => stats = true,
+            "--stats-summary" => {
+                let period = args.next().ok_or("--stats-summary needs day, week or month")?;
+                summary_days = Some(match period.as_str() {
+                    "day" => 1,
+                    "week" => 7,
+                    "month" => 30,
+                    other => return Err(format!("unknown period: {other}").into()),
+                });
+            }
+            "--stats-export" => export = Some(args.next().ok_or("--stats-export needs a file")?),
+            "--stats-import" => import = Some(args.next().ok_or("--stats-import needs a file")?),
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a number")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed: {value}"))?);
+            }
+            "--profile" => {
+                let value = args.next().ok_or("--profile needs casual, normal or hardcore")?;
+                profile = Some(game::Profile::parse(&value).ok_or_else(|| format!("unknown profile: {value}"))?);
+            }
+            "--replay-game" => {
+                replay = Some(args.next().ok_or("--replay-game needs a replay file")?)
+            }
+            "--daemon" => daemon = true,
+            "--attach" => attach = true,
+            // Optionally followed by the host:port of a game watched over TCP.
+            "--watch" => watch = Some(args.next_if(|a| !a.starts_with('-'))),
+            "--autoplay" => autoplay = true,
+            "--log-file" => log_file = Some(args.next().ok_or("--log-file needs a path")?),
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            other => return Err(format!("unknown argument: {other}").into()),
+        }
+    }
+    if let Some(path) = &log_file {
+        logging::init(path, verbosity).map_err(|e| format!("{path}: {e}"))?;
+    } else if verbosity > 0 {
+        return Err("-v needs --log-file <path>".into());
+    }
+    if let Some(path) = export {
+        print!("{}", persist::export_stats(&path)?);
+        return Ok(());
+    }
+    if let Some(path) = import {
+        print!("{}", persist::import_stats(&path)?);
+        return Ok(());
+    }
+    if let Some(days) = summary_days {
+        print!("{}", persist::period_summary(days)?);
+        return Ok(());
+    }
+    if stats {
+        // The longest waits live outside the database, so they print either way.
+        match persist::lifetime_report() {
+            Ok(report) => println!("{report}"),
+            Err(e) => eprintln!("{e}\n"),
+        }
+        print!("{}", persist::longest_waits_report());
+        return Ok(());
+    }
+    if let Some(path) = replay {
+        return Ok(app::playback(&path)?);
+    }
+    if attach {
+        return Ok(app::attach()?);
+    }
+    if let Some(addr) = watch {
+        return Ok(app::watch(addr.as_deref())?);
+    }
+    match script {
+        // Simulation never draws, so --headless is implied.
+        Some(path) => Ok(app::simulate(&path, render, seed)?),
+        None if headless || render => Err("--headless and --render only apply to --simulate <events-file>".into()),
+        None if daemon => Ok(app::daemon(seed, profile, autoplay)?),
+        None => Ok(app::run(seed, profile, autoplay)?),
+    }
 }
@@ -0,0 +1,80 @@
+// Where waitris keeps things, per the XDG base-directory spec, for the game and the launcher.
+use std::env;
+use std::fs::DirBuilder;
+use std::io;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+
+const APP: &str = "waitris";
+
+// An XDG variable only counts when it is set to an absolute path.
+fn xdg(var: &str) -> Option<PathBuf> {
+    let dir = PathBuf::from(env::var_os(var)?);
+    dir.is_absolute().then_some(dir)
+}
+
+fn home() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+// $XDG_CONFIG_HOME/waitris, else ~/.config/waitris on every platform: the shell hook lives
+// here and rc files already point at it.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = xdg("XDG_CONFIG_HOME") {
+        return Some(dir.join(APP));
+    }
+    Some(home()?.join(".config").join(APP))
+}
+
+// $XDG_DATA_HOME/waitris, else ~/.local/share/waitris. macOS falls back to Application Support
+// instead, unless an older build already left its data in ~/.local/share.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = xdg("XDG_DATA_HOME") {
+        return Some(dir.join(APP));
+    }
+    let home = home()?;
+    let xdg_default = home.join(".local").join("share").join(APP);
+    if cfg!(target_os = "macos") && !xdg_default.exists() {
+        return Some(home.join("Library").join("Application Support").join(APP));
+    }
+    Some(xdg_default)
+}
+
+// $XDG_RUNTIME_DIR/waitris when the session provides one; otherwise a per-user directory in
+// $TMPDIR (macOS sets it per user) or /tmp.
+pub fn runtime_dir() -> PathBuf {
+    if let Some(dir) = xdg("XDG_RUNTIME_DIR") {
+        return dir.join(APP);
+    }
+    let tmp = xdg("TMPDIR").unwrap_or_else(|| PathBuf::from("/tmp"));
+    let user = env::var("USER").unwrap_or_else(|_| "user".to_string());
+    tmp.join(format!("{APP}-{user}"))
+}
+
+// The shell hook computes the same path; STACK_SOCK overrides it on both sides.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = env::var_os("STACK_SOCK").filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+    runtime_dir().join("stack-game.sock")
+}
+
+// Where spectators of that game connect (STACK_WATCH=1), named after its socket.
+pub fn watch_socket_path() -> PathBuf {
+    socket_path().with_extension("watch.sock")
+}
+
+// Left beside a socket by a game quit on purpose, so the guard doesn't bring it back.
+pub fn quit_marker_path(socket: &Path) -> PathBuf {
+    socket.with_extension("quit")
+}
+
+// Runtime files are private to the user, as the spec asks.
+pub fn ensure_socket_dir() -> io::Result<()> {
+    match socket_path().parent() {
+        Some(dir) => DirBuilder::new().recursive(true).mode(0o700).create(dir),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Mode;
+use crate::Game;
+
+use super::data_dir;
+use super::file::{load, save};
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PersonalBest {
+    pub score: u64,
+    // Fastest finished Sprint; the other modes only keep a score.
+    pub time_ms: Option<u64>,
+}
+
+impl PersonalBest {
+    pub fn time(&self) -> Option<Duration> {
+        self.time_ms.map(Duration::from_millis)
+    }
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PersonalBests {
+    pub classic: PersonalBest,
+    pub zen: PersonalBest,
+    pub sprint: PersonalBest,
+    pub ultra: PersonalBest,
+}
+
+impl PersonalBests {
+    pub fn get(&self, mode: Mode) -> PersonalBest {
+        match mode {
+            Mode::Classic => self.classic,
+            Mode::Zen => self.zen,
+            Mode::Sprint => self.sprint,
+            Mode::Ultra => self.ultra,
+        }
+    }
+
+    fn get_mut(&mut self, mode: Mode) -> &mut PersonalBest {
+        match mode {
+            Mode::Classic => &mut self.classic,
+            Mode::Zen => &mut self.zen,
+            Mode::Sprint => &mut self.sprint,
+            Mode::Ultra => &mut self.ultra,
+        }
+    }
+
+    // Fold a game into its mode's bests; true if it beat one. A Sprint only sets a time once
+    // the 40 lines are done.
+    pub fn record(&mut self, game: &Game) -> bool {
+        let best = self.get_mut(game.mode);
+        let mut improved = false;
+        if game.score > best.score {
+            best.score = game.score;
+            improved = true;
+        }
+        if game.mode == Mode::Sprint && game.goal_reached() {
+            let ms = game.play_time().as_millis() as u64;
+            if best.time_ms.is_none_or(|t| ms < t) {
+                best.time_ms = Some(ms);
+                improved = true;
+            }
+        }
+        improved
+    }
+
+    // Keep the better of each mode's score and time.
+    pub fn merge(&mut self, other: &PersonalBests) {
+        for mode in [Mode::Classic, Mode::Zen, Mode::Sprint, Mode::Ultra] {
+            let theirs = other.get(mode);
+            let best = self.get_mut(mode);
+            best.score = best.score.max(theirs.score);
+            best.time_ms = match (best.time_ms, theirs.time_ms) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+}
+
+fn bests_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("bests.json"))
+}
+
+// Before bests were kept per mode there was a single best score, all from command-fed games.
+fn legacy_best_score() -> u64 {
+    data_dir()
+        .and_then(|dir| fs::read_to_string(dir.join("best_score")).ok())
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn load_personal_bests() -> PersonalBests {
+    load(bests_path(), "personal bests").unwrap_or_else(|| {
+        let mut bests = PersonalBests::default();
+        bests.classic.score = legacy_best_score();
+        bests
+    })
+}
+
+pub fn save_personal_bests(bests: &PersonalBests) -> io::Result<()> {
+    save(bests_path(), bests, true)
+}
@@ -0,0 +1,95 @@
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Layout version stamped into every saved file's header.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Header<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+    data: Value,
+}
+
+// Bring data written by an older build up to FORMAT_VERSION. Files from before the header
+// existed count as version 0 and already have the version 1 layout.
+fn migrate(version: u32, data: Value) -> io::Result<Value> {
+    match version {
+        0 | 1 => Ok(data),
+        v => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("written by a newer build (format {v}, this one reads up to {FORMAT_VERSION})"),
+        )),
+    }
+}
+
+// Write beside the target, flush it to disk, then rename over: a crash leaves either the old
+// file or the new one, never half of each.
+pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+pub(super) fn write_versioned<T: Serialize>(path: &Path, data: &T, pretty: bool) -> io::Result<()> {
+    let header = Header {
+        version: FORMAT_VERSION,
+        data,
+    };
+    let text = if pretty {
+        serde_json::to_string_pretty(&header)?
+    } else {
+        serde_json::to_string(&header)?
+    };
+    write_atomic(path, text.as_bytes())
+}
+
+pub(super) fn read_versioned<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let text = fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&text)?;
+    let (version, data) = match serde_json::from_value::<Versioned>(value.clone()) {
+        Ok(v) => (v.version, v.data),
+        Err(_) => (0, value),
+    };
+    Ok(serde_json::from_value(migrate(version, data)?)?)
+}
+
+// A file in the data dir, or None if it's missing or unreadable. An unreadable one is moved
+// aside to <name>.corrupt rather than left for the next save to overwrite.
+pub(super) fn load<T: DeserializeOwned>(path: Option<PathBuf>, what: &str) -> Option<T> {
+    let path = path?;
+    match read_versioned(&path) {
+        Ok(data) => Some(data),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            log::warn!("ignoring unreadable {what}: {e}");
+            let mut aside = OsString::from(path.as_os_str());
+            aside.push(".corrupt");
+            let _ = fs::rename(&path, aside);
+            None
+        }
+    }
+}
+
+pub(super) fn save<T: Serialize>(path: Option<PathBuf>, data: &T, pretty: bool) -> io::Result<()> {
+    let path = path.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME not set"))?;
+    write_versioned(&path, data, pretty)
+}
@@ -0,0 +1,361 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Mode, Profile};
+
+// A finished shell command, as the lifetime store keeps it.
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+pub struct CommandRow<'a> {
+    pub command: &'a str,
+    pub identity: &'a str,
+    pub duration: Duration,
+    pub exit_code: i32,
+    pub pieces: u32,
+}
+
+// One game, recorded when it ends or is abandoned by a restart or quit.
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+pub struct GameRow {
+    pub mode: Mode,
+    pub profile: Profile,
+    pub score: u64,
+    pub lines: u64,
+    pub pieces: u32,
+    pub bombs_earned: u32,
+    pub bombs_used: u32,
+    pub play_time: Duration,
+}
+
+// Rows as they sit in the store, timestamp included, for moving stats between machines.
+#[derive(Serialize, Deserialize)]
+pub struct StoredCommand {
+    pub at: i64,
+    pub command: String,
+    pub identity: String,
+    pub duration_ms: i64,
+    pub exit_code: i32,
+    pub pieces: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StoredGame {
+    pub at: i64,
+    pub mode: String,
+    pub profile: String,
+    pub score: i64,
+    pub lines: i64,
+    pub pieces: i64,
+    pub bombs_earned: i64,
+    pub bombs_used: i64,
+    pub play_ms: i64,
+}
+
+pub use store::{
+    export_history, import_history, lifetime_report, period_summary, record_command, record_game,
+};
+
+#[cfg(feature = "sqlite")]
+mod store {
+    use std::error::Error;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use rusqlite::{params, Connection};
+
+    use crate::persist::data_dir;
+
+    use super::{CommandRow, GameRow, StoredCommand, StoredGame};
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS commands (
+            at INTEGER NOT NULL,
+            command TEXT NOT NULL,
+            identity TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            exit_code INTEGER NOT NULL,
+            pieces INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS games (
+            at INTEGER NOT NULL,
+            mode TEXT NOT NULL,
+            profile TEXT NOT NULL,
+            score INTEGER NOT NULL,
+            lines INTEGER NOT NULL,
+            pieces INTEGER NOT NULL,
+            bombs_earned INTEGER NOT NULL,
+            bombs_used INTEGER NOT NULL,
+            play_ms INTEGER NOT NULL
+        );";
+
+    // Schema version kept in the database's user_version. Each step upgrades one version;
+    // version 0 is a fresh file or one from before the stamp, which SCHEMA already matches.
+    const SCHEMA_VERSION: i64 = 1;
+
+    fn migrate(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(format!("stats.db is from a newer build (schema {version})").into());
+        }
+        if version < 1 {
+            conn.execute_batch(SCHEMA)?;
+        }
+        if version < SCHEMA_VERSION {
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        Ok(())
+    }
+
+    fn unix_now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64)
+    }
+
+    fn db_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("stats.db"))
+    }
+
+    fn open() -> Result<Connection, Box<dyn Error>> {
+        let path = db_path().ok_or("HOME not set")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        migrate(&conn)?;
+        Ok(conn)
+    }
+
+    pub fn record_command(row: &CommandRow) -> Result<(), Box<dyn Error>> {
+        open()?.execute(
+            "INSERT INTO commands (at, command, identity, duration_ms, exit_code, pieces)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                unix_now(),
+                row.command,
+                row.identity,
+                row.duration.as_millis() as i64,
+                row.exit_code,
+                row.pieces
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_game(row: &GameRow) -> Result<(), Box<dyn Error>> {
+        open()?.execute(
+            "INSERT INTO games
+                (at, mode, profile, score, lines, pieces, bombs_earned, bombs_used, play_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                unix_now(),
+                row.mode.name(),
+                row.profile.name(),
+                row.score as i64,
+                row.lines as i64,
+                row.pieces,
+                row.bombs_earned,
+                row.bombs_used,
+                row.play_time.as_millis() as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn export_history() -> Result<(Vec<StoredCommand>, Vec<StoredGame>), Box<dyn Error>> {
+        let conn = open()?;
+        let commands = conn
+            .prepare(
+                "SELECT at, command, identity, duration_ms, exit_code, pieces FROM commands ORDER BY at",
+            )?
+            .query_map([], |r| {
+                Ok(StoredCommand {
+                    at: r.get(0)?,
+                    command: r.get(1)?,
+                    identity: r.get(2)?,
+                    duration_ms: r.get(3)?,
+                    exit_code: r.get(4)?,
+                    pieces: r.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let games = conn
+            .prepare(
+                "SELECT at, mode, profile, score, lines, pieces, bombs_earned, bombs_used, play_ms
+                 FROM games ORDER BY at",
+            )?
+            .query_map([], |r| {
+                Ok(StoredGame {
+                    at: r.get(0)?,
+                    mode: r.get(1)?,
+                    profile: r.get(2)?,
+                    score: r.get(3)?,
+                    lines: r.get(4)?,
+                    pieces: r.get(5)?,
+                    bombs_earned: r.get(6)?,
+                    bombs_used: r.get(7)?,
+                    play_ms: r.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((commands, games))
+    }
+
+    // A row with the same timestamp and key columns is the same event seen twice (re-importing
+    // a file, or a file that came from here), so it's skipped. Returns how many rows were new.
+    pub fn import_history(
+        commands: &[StoredCommand],
+        games: &[StoredGame],
+    ) -> Result<(usize, usize), Box<dyn Error>> {
+        let mut conn = open()?;
+        let tx = conn.transaction()?;
+        let mut new_commands = 0;
+        for c in commands {
+            new_commands += tx.execute(
+                "INSERT INTO commands (at, command, identity, duration_ms, exit_code, pieces)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM commands WHERE at = ?1 AND command = ?2 AND duration_ms = ?4
+                 )",
+                params![c.at, c.command, c.identity, c.duration_ms, c.exit_code, c.pieces],
+            )?;
+        }
+        let mut new_games = 0;
+        for g in games {
+            new_games += tx.execute(
+                "INSERT INTO games
+                    (at, mode, profile, score, lines, pieces, bombs_earned, bombs_used, play_ms)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM games WHERE at = ?1 AND mode = ?2 AND score = ?4 AND play_ms = ?9
+                 )",
+                params![
+                    g.at,
+                    g.mode,
+                    g.profile,
+                    g.score,
+                    g.lines,
+                    g.pieces,
+                    g.bombs_earned,
+                    g.bombs_used,
+                    g.play_ms
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok((new_commands, new_games))
+    }
+
+    fn hours_minutes(ms: i64) -> String {
+        let secs = ms / 1000;
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+
+    // What the last `days` days added up to, for `waitris stats summary`.
+    pub fn period_summary(days: u32) -> Result<String, Box<dyn Error>> {
+        let conn = open()?;
+        let since = unix_now() - i64::from(days) * 86_400;
+        let (commands, waited_ms): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(duration_ms), 0) FROM commands WHERE at >= ?1",
+            [since],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        let (lines, best): (i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(lines), 0), COALESCE(MAX(score), 0) FROM games WHERE at >= ?1",
+            [since],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+
+        let plural = if days == 1 { "" } else { "s" };
+        let mut out = format!("last {days} day{plural}\n");
+        let mut row = |label: &str, value: String| out.push_str(&format!("{label:<16}{value}\n"));
+        row("commands waited", commands.to_string());
+        row("time waiting", hours_minutes(waited_ms));
+        row("lines cleared", lines.to_string());
+        row("best score", best.to_string());
+        Ok(out)
+    }
+
+    // Plain-text aggregates for `waitris stats`.
+    pub fn lifetime_report() -> Result<String, Box<dyn Error>> {
+        let conn = open()?;
+        let (games, best, lines): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(MAX(score), 0), COALESCE(SUM(lines), 0) FROM games",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+        let (commands, failed, waited_ms, pieces): (i64, i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(exit_code != 0), 0), COALESCE(SUM(duration_ms), 0),
+                    COALESCE(SUM(pieces), 0)
+             FROM commands",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )?;
+
+        let mut out = String::new();
+        let mut row = |label: &str, value: String| out.push_str(&format!("{label:<16}{value}\n"));
+        row("games played", games.to_string());
+        row("best score", best.to_string());
+        row("lines cleared", lines.to_string());
+        row("commands run", format!("{commands} ({failed} failed)"));
+        row("time waiting", hours_minutes(waited_ms));
+        row("pieces dealt", pieces.to_string());
+
+        let mut top = conn.prepare(
+            "SELECT identity, SUM(pieces) AS total FROM commands
+             GROUP BY identity HAVING total > 0 ORDER BY total DESC LIMIT 5",
+        )?;
+        let top = top
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some((identity, total)) = top.first() {
+            out.push_str(&format!(
+                "\nmost pieces generated by: {identity} ({}%)\n",
+                total * 100 / pieces
+            ));
+            for (identity, total) in &top {
+                out.push_str(&format!("  {identity:<14}{total:>6}  {:>3}%\n", total * 100 / pieces));
+            }
+        }
+        Ok(out)
+    }
+}
+
+// Without the feature the game still calls in; nothing is kept.
+#[cfg(not(feature = "sqlite"))]
+mod store {
+    use std::error::Error;
+
+    use super::{CommandRow, GameRow, StoredCommand, StoredGame};
+
+    pub fn record_command(_row: &CommandRow) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    pub fn record_game(_row: &GameRow) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    pub fn lifetime_report() -> Result<String, Box<dyn Error>> {
+        Err("lifetime stats need a build with `--features sqlite`".into())
+    }
+
+    pub fn period_summary(_days: u32) -> Result<String, Box<dyn Error>> {
+        lifetime_report()
+    }
+
+    pub fn export_history() -> Result<(Vec<StoredCommand>, Vec<StoredGame>), Box<dyn Error>> {
+        Ok((Vec::new(), Vec::new()))
+    }
+
+    pub fn import_history(
+        commands: &[StoredCommand],
+        games: &[StoredGame],
+    ) -> Result<(usize, usize), Box<dyn Error>> {
+        if commands.is_empty() && games.is_empty() {
+            return Ok((0, 0));
+        }
+        Err("skipped the command and game history: it needs a build with `--features sqlite`".into())
+    }
+}
@@ -0,0 +1,25 @@
+mod best;
+mod file;
+mod history;
+mod replay;
+mod scores;
+mod session;
+mod sign;
+mod snapshot;
+mod streak;
+mod transfer;
+mod waits;
+
+pub use best::{load_personal_bests, save_personal_bests, PersonalBests};
+pub use history::{lifetime_report, period_summary, record_command, record_game, CommandRow, GameRow};
+pub use replay::{load_replay, save_replay};
+pub use scores::{insert_high_score, load_high_scores, save_high_scores, ScoreEntry};
+pub use session::save_session_summary;
+pub use snapshot::{clear_snapshot, load_snapshot, save_snapshot};
+pub use streak::{load_streak, save_streak};
+pub use transfer::{export_stats, import_stats};
+pub use waits::{
+    insert_longest_wait, load_longest_waits, longest_waits_report, save_longest_waits, WaitEntry,
+};
+
+pub use crate::paths::data_dir;
@@ -0,0 +1,20 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::game::Recording;
+
+use super::data_dir;
+use super::file::{read_versioned, save};
+
+// The most recent session, overwritten each time; play it with --replay-game.
+fn replay_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("replay.json"))
+}
+
+pub fn save_replay(recording: &Recording) -> io::Result<()> {
+    save(replay_path(), recording, false)
+}
+
+pub fn load_replay(path: &Path) -> io::Result<Recording> {
+    read_versioned(path)
+}
@@ -0,0 +1,162 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Mode;
+use crate::MAX_HIGH_SCORES;
+
+use super::data_dir;
+use super::file::{load, save};
+use super::sign::install_key;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub score: u64,
+    pub lines: u64,
+    pub mode: Mode,
+    pub date: String,
+    pub summary: String,
+    // Failed its signature check once; stays set from then on.
+    #[serde(default)]
+    pub flagged: bool,
+    // Came in with another install's stats, signed with a key this one doesn't have, so it's
+    // never checked or signed here.
+    #[serde(default)]
+    pub imported: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+// The same run seen twice, whichever install signed it.
+impl PartialEq for ScoreEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.signed_fields() == other.signed_fields()
+    }
+}
+
+impl ScoreEntry {
+    pub fn new(score: u64, lines: u64, mode: Mode, summary: String) -> Self {
+        Self {
+            score,
+            lines,
+            mode,
+            date: today(),
+            summary,
+            flagged: false,
+            imported: false,
+            signature: None,
+        }
+    }
+
+    fn signed_fields(&self) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.score,
+            self.lines,
+            self.mode.name(),
+            self.date,
+            self.flagged,
+            self.summary
+        )
+    }
+}
+
+fn high_scores_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("high_scores.json"))
+}
+
+// Entries whose signature doesn't match are flagged, not dropped. Unsigned ones are only
+// trusted on the run that creates the install key, since they were written before signing.
+// Imported entries are left as they came.
+pub fn load_high_scores() -> Vec<ScoreEntry> {
+    let mut entries: Vec<ScoreEntry> = load(high_scores_path(), "high scores").unwrap_or_default();
+    let key = match install_key() {
+        Ok(key) => key,
+        Err(e) => {
+            log::warn!("cannot check high scores: {e}");
+            return entries;
+        }
+    };
+    for entry in entries.iter_mut().filter(|e| !e.imported) {
+        let genuine = match &entry.signature {
+            Some(signature) => key.verify(&entry.signed_fields(), signature),
+            None => key.fresh,
+        };
+        if !genuine && !entry.flagged {
+            log::warn!("high score {} from {} was edited", entry.score, entry.date);
+            entry.flagged = true;
+        }
+    }
+    // Sign the pre-signing entries now, while the key is still new enough to vouch for them.
+    if key.fresh
+        && entries.iter().any(|e| e.signature.is_none() && !e.imported)
+        && let Err(e) = save_high_scores(&entries)
+    {
+        log::warn!("cannot sign high scores: {e}");
+    }
+    entries
+}
+
+// Only this install's own new entries get signed. One that already carries a signature keeps
+// it, so a flagged entry stays flagged and an imported one is never vouched for here.
+pub fn save_high_scores(entries: &[ScoreEntry]) -> io::Result<()> {
+    let mut entries = entries.to_vec();
+    let unsigned = |e: &ScoreEntry| e.signature.is_none() && !e.imported;
+    if entries.iter().any(unsigned) {
+        match install_key() {
+            Ok(key) => {
+                for entry in entries.iter_mut().filter(|e| unsigned(e)) {
+                    entry.signature = Some(key.sign(&entry.signed_fields()));
+                }
+            }
+            Err(e) => log::warn!("saving high scores unsigned: {e}"),
+        }
+    }
+    save(high_scores_path(), &entries, true)
+}
+
+// Slot the entry into the table, returning its rank if it made the cut. Ties go below older
+// entries so an earlier run keeps its place.
+pub fn insert_high_score(entries: &mut Vec<ScoreEntry>, entry: ScoreEntry) -> Option<usize> {
+    let rank = entries.iter().position(|e| entry.score > e.score).unwrap_or(entries.len());
+    if rank >= MAX_HIGH_SCORES {
+        return None;
+    }
+    entries.insert(rank, entry);
+    entries.truncate(MAX_HIGH_SCORES);
+    Some(rank)
+}
+
+// Union of two tables, the other one's entries marked imported. Equal scores rank by date,
+// earlier first, the same rule as a tie within one machine.
+pub fn merge_high_scores(entries: &mut Vec<ScoreEntry>, other: &[ScoreEntry]) -> usize {
+    let before = entries.len();
+    for entry in other {
+        if !entries.contains(entry) {
+            entries.push(ScoreEntry { imported: true, ..entry.clone() });
+        }
+    }
+    let added = entries.len() - before;
+    entries.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.date.cmp(&b.date)));
+    entries.truncate(MAX_HIGH_SCORES);
+    added
+}
+
+// UTC calendar date, converted from days since the epoch (Hinnant's civil_from_days).
+pub(super) fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
@@ -0,0 +1,18 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::game::SessionSummary;
+
+use super::data_dir;
+use super::file::write_atomic;
+
+fn session_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("session.json"))
+}
+
+// Read by other tools and matched by the socket QUERY reply, so no version header.
+pub fn save_session_summary(summary: &SessionSummary) -> io::Result<()> {
+    let path = session_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME not set"))?;
+    write_atomic(&path, serde_json::to_string_pretty(summary)?.as_bytes())
+}
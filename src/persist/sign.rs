@@ -0,0 +1,72 @@
+use std::fs;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::data_dir;
+use super::file::write_atomic;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// A secret made once per install. It only proves a record was written by this install's game,
+// not that nobody could forge one: enough to catch a hand-edited high-score file.
+pub(super) struct InstallKey {
+    bytes: Vec<u8>,
+    // Made just now, so anything unsigned on disk predates signing rather than being edited.
+    pub fresh: bool,
+}
+
+fn key_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("install.key"))
+}
+
+pub(super) fn install_key() -> io::Result<InstallKey> {
+    let path = key_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME not set"))?;
+    match fs::read_to_string(&path) {
+        Ok(text) => {
+            let bytes = from_hex(text.trim())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed install.key"))?;
+            Ok(InstallKey { bytes, fresh: false })
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let bytes: Vec<u8> = (0..32).map(|_| rand::random::<u8>()).collect();
+            write_atomic(&path, to_hex(&bytes).as_bytes())?;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            Ok(InstallKey { bytes, fresh: true })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+impl InstallKey {
+    fn mac(&self, message: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.bytes).expect("HMAC takes any key length");
+        mac.update(message.as_bytes());
+        mac
+    }
+
+    pub(super) fn sign(&self, message: &str) -> String {
+        to_hex(&self.mac(message).finalize().into_bytes())
+    }
+
+    pub(super) fn verify(&self, message: &str, signature: &str) -> bool {
+        from_hex(signature).is_some_and(|sig| self.mac(message).verify_slice(&sig).is_ok())
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,26 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::Game;
+
+use super::data_dir;
+use super::file::{load, save};
+
+fn snapshot_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("snapshot.json"))
+}
+
+pub fn load_snapshot() -> Option<Game> {
+    load(snapshot_path(), "snapshot")
+}
+
+pub fn save_snapshot(game: &Game) -> io::Result<()> {
+    save(snapshot_path(), game, false)
+}
+
+pub fn clear_snapshot() {
+    if let Some(path) = snapshot_path() {
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,19 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::game::VarietyStreak;
+
+use super::data_dir;
+use super::file::{load, save};
+
+fn streak_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("streak.json"))
+}
+
+pub fn load_streak() -> Option<VarietyStreak> {
+    load(streak_path(), "streak")
+}
+
+pub fn save_streak(streak: &VarietyStreak) -> io::Result<()> {
+    save(streak_path(), streak, false)
+}
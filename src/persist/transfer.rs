@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::best::{load_personal_bests, save_personal_bests, PersonalBests};
+use super::file::{read_versioned, write_versioned};
+use super::history::{export_history, import_history, StoredCommand, StoredGame};
+use super::scores::{load_high_scores, merge_high_scores, save_high_scores, ScoreEntry};
+use super::waits::{load_longest_waits, merge_longest_waits, save_longest_waits, WaitEntry};
+
+// Everything `waitris stats` knows, in one file that can be carried to another machine.
+#[derive(Serialize, Deserialize)]
+struct StatsExport {
+    exported_at: u64,
+    #[serde(default)]
+    bests: PersonalBests,
+    #[serde(default)]
+    high_scores: Vec<ScoreEntry>,
+    #[serde(default)]
+    longest_waits: Vec<WaitEntry>,
+    #[serde(default)]
+    commands: Vec<StoredCommand>,
+    #[serde(default)]
+    games: Vec<StoredGame>,
+}
+
+pub fn export_stats(path: &str) -> Result<String, Box<dyn Error>> {
+    let (commands, games) = export_history()?;
+    let export = StatsExport {
+        exported_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        bests: load_personal_bests(),
+        high_scores: load_high_scores(),
+        longest_waits: load_longest_waits(),
+        commands,
+        games,
+    };
+    write_versioned(Path::new(path), &export, true).map_err(|e| format!("{path}: {e}"))?;
+    Ok(format!(
+        "exported {} commands, {} games, {} high scores to {path}\n",
+        export.commands.len(),
+        export.games.len(),
+        export.high_scores.len()
+    ))
+}
+
+// Merge another machine's export into this one. The small tables are written first, so a build
+// without the store still picks those up before the history import fails.
+pub fn import_stats(path: &str) -> Result<String, Box<dyn Error>> {
+    let import: StatsExport = read_versioned(Path::new(path)).map_err(|e| format!("{path}: {e}"))?;
+
+    let mut bests = load_personal_bests();
+    bests.merge(&import.bests);
+    save_personal_bests(&bests)?;
+    let mut scores = load_high_scores();
+    let new_scores = merge_high_scores(&mut scores, &import.high_scores);
+    save_high_scores(&scores)?;
+    let mut waits = load_longest_waits();
+    let new_waits = merge_longest_waits(&mut waits, &import.longest_waits);
+    save_longest_waits(&waits)?;
+    let mut out = format!("merged personal bests, {new_scores} high scores, {new_waits} longest waits\n");
+
+    let (new_commands, new_games) = import_history(&import.commands, &import.games)?;
+    out.push_str(&format!(
+        "imported {new_commands} of {} commands, {new_games} of {} games\n",
+        import.commands.len(),
+        import.games.len()
+    ));
+    Ok(out)
+}
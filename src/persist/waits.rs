@@ -0,0 +1,107 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::MAX_LONGEST_WAITS;
+
+use super::data_dir;
+use super::file::{load, save};
+use super::scores::today;
+
+// One of the longest commands ever waited on, with what the game made of it.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaitEntry {
+    pub command: String,
+    pub secs: f64,
+    pub exit_code: i32,
+    pub date: String,
+    pub recap: String,
+}
+
+impl WaitEntry {
+    pub fn new(command: &str, duration: Duration, exit_code: i32, recap: String) -> Self {
+        Self {
+            command: command.to_string(),
+            secs: duration.as_millis() as f64 / 1000.0,
+            exit_code,
+            date: today(),
+            recap,
+        }
+    }
+}
+
+fn longest_waits_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("longest_waits.json"))
+}
+
+pub fn load_longest_waits() -> Vec<WaitEntry> {
+    load(longest_waits_path(), "longest waits").unwrap_or_default()
+}
+
+pub fn save_longest_waits(entries: &[WaitEntry]) -> io::Result<()> {
+    save(longest_waits_path(), &entries, true)
+}
+
+// Same slotting rule as the high scores: ties go below the older wait.
+pub fn insert_longest_wait(entries: &mut Vec<WaitEntry>, entry: WaitEntry) -> Option<usize> {
+    let rank = entries.iter().position(|e| entry.secs > e.secs).unwrap_or(entries.len());
+    if rank >= MAX_LONGEST_WAITS {
+        return None;
+    }
+    entries.insert(rank, entry);
+    entries.truncate(MAX_LONGEST_WAITS);
+    Some(rank)
+}
+
+pub fn merge_longest_waits(entries: &mut Vec<WaitEntry>, other: &[WaitEntry]) -> usize {
+    let before = entries.len();
+    for entry in other {
+        if !entries.contains(entry) {
+            entries.push(entry.clone());
+        }
+    }
+    let added = entries.len() - before;
+    entries.sort_by(|a, b| b.secs.total_cmp(&a.secs).then_with(|| a.date.cmp(&b.date)));
+    entries.truncate(MAX_LONGEST_WAITS);
+    added
+}
+
+// Plain-text table for `--stats`.
+pub fn longest_waits_report() -> String {
+    let entries = load_longest_waits();
+    let mut out = String::from("longest waits\n");
+    if entries.is_empty() {
+        out.push_str("  none yet\n");
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>3}. {:>8}  {}  {}\n       {}{}\n",
+            i + 1,
+            wait_time(entry.secs),
+            entry.date,
+            entry.command,
+            entry.recap,
+            failed(entry.exit_code),
+        ));
+    }
+    out
+}
+
+fn wait_time(secs: f64) -> String {
+    let secs = secs as u64;
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+fn failed(exit_code: i32) -> String {
+    if exit_code == 0 {
+        String::new()
+    } else {
+        format!(", exit {exit_code}")
+    }
+}
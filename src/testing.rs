@@ -0,0 +1,131 @@
+// Drive a game without a terminal: build one from a board fixture, feed it a script of socket
+// lines and moves, and read the board back. Fixtures are rows of `.` (empty) and `#` (filled),
+// top to bottom, standing on the floor; rows above them are empty.
+use std::time::Duration;
+
+use crate::game::{Action, Board, Cell, Mode, Origin, Piece, Profile, Shape};
+use crate::io::parse_command_line;
+use crate::{Game, BOARD_H, BOARD_W, CHUNK_SIZE};
+
+pub fn board(fixture: &str) -> Board {
+    let rows: Vec<&str> = fixture.lines().map(str::trim).filter(|row| !row.is_empty()).collect();
+    assert!(rows.len() <= BOARD_H, "fixture has {} rows, the board {BOARD_H}", rows.len());
+    let mut board = Board::new(BOARD_W, BOARD_H);
+    let top = BOARD_H - rows.len();
+    for (dy, row) in rows.iter().enumerate() {
+        assert_eq!(row.chars().count(), BOARD_W, "fixture row {row:?} isn't {BOARD_W} wide");
+        for (x, ch) in row.chars().enumerate() {
+            if ch != '.' {
+                board.set(x, top + dy, Cell::Filled('#', '░', Origin::Filler));
+            }
+        }
+    }
+    board
+}
+
+// A Classic game under a fixed seed, already started, standing on the fixture with nothing
+// falling yet.
+pub fn game(fixture: &str) -> Game {
+    let mut game = Game::with_seed(0);
+    game.start(Mode::Classic, Profile::Normal);
+    game.board = board(fixture);
+    game
+}
+
+// Panic, showing both, unless the bottom of the board matches `expected` and everything above
+// it is empty.
+pub fn assert_board(game: &Game, expected: &str) {
+    let actual = game.board.to_string();
+    let wanted = board(expected).to_string();
+    assert!(actual == wanted, "board differs\nexpected:\n{wanted}\nactual:\n{actual}");
+}
+
+// Drop a given piece straight down from column `x` and let whatever it clears collapse.
+pub fn place(game: &mut Game, shape: Shape, rotation: u8, x: i32) {
+    let mut piece = Piece::with_payload(shape, vec!['#'; CHUNK_SIZE]);
+    piece.rotation = rotation;
+    piece.x = x;
+    drop(game, piece, false);
+}
+
+// Drop a bomb, the 2x2 block the game deals, with its left column at `x`.
+pub fn place_bomb(game: &mut Game, x: i32) {
+    let mut piece = Piece::with_payload(Shape::O, vec!['▓'; CHUNK_SIZE]).with_origin(Origin::Bomb);
+    // The O's cells sit one column in from the piece's own x.
+    piece.x = x - 1;
+    drop(game, piece, true);
+}
+
+fn drop(game: &mut Game, piece: Piece, bomb: bool) {
+    game.current = piece;
+    game.current_is_bomb = bomb;
+    game.active_piece = true;
+    game.input(Action::HardDrop);
+    settle(game);
+}
+
+// One gravity interval of play: exactly one tick, with the game clock moving to match.
+pub fn tick(game: &mut Game) {
+    game.step(None, game.gravity_interval());
+    settle(game);
+}
+
+// Play out the clear flash immediately; there is no one watching it.
+pub fn settle(game: &mut Game) {
+    while !game.pending_clear.is_empty() {
+        game.process_effects();
+    }
+}
+
+// Socket lines (START/OUTPUT/END) plus TICK [n] and LEFT/RIGHT/DOWN/ROTATE/DROP, one per line.
+// Blank lines and `#` comments are skipped; errors name the line.
+pub fn run_script(game: &mut Game, script: &str) -> Result<(), String> {
+    for (n, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(ev) = parse_command_line(line) {
+            game.handle_command_event(ev);
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let input = match words.next().unwrap_or_default() {
+            "TICK" => {
+                let ticks = match words.next() {
+                    Some(count) => count
+                        .parse()
+                        .map_err(|_| format!("{}: bad tick count {count:?}", n + 1))?,
+                    None => 1,
+                };
+                for _ in 0..ticks {
+                    tick(game);
+                }
+                continue;
+            }
+            "LEFT" => Action::Left,
+            "RIGHT" => Action::Right,
+            "DOWN" => Action::SoftDrop,
+            "ROTATE" => Action::Rotate,
+            "DROP" => Action::HardDrop,
+            _ => return Err(format!("{}: unrecognised line {line:?}", n + 1)),
+        };
+        game.step(Some(input), Duration::ZERO);
+        settle(game);
+    }
+    Ok(())
+}
+
+// The frame the terminal UI draws for `game` on a `width` by `height` terminal, as rows of
+// text, with the pause menu over it if `paused`.
+#[cfg(feature = "tui")]
+pub fn render_to_string(game: &Game, width: u16, height: u16, paused: bool) -> String {
+    use crate::persist::PersonalBests;
+    use crate::ui::{Hud, Menu, Overlay, Theme};
+
+    let mut hud = Hud::new(PersonalBests::default(), Theme::default());
+    if paused {
+        hud.overlay = Overlay::Paused(Menu::pause());
+    }
+    crate::app::render_frame(game, &hud, width, height).expect("an off-screen frame")
+}
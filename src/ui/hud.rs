@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use crate::game::{CommandLog, SessionClock, SessionTotals};
+use crate::persist::{PersonalBests, ScoreEntry, WaitEntry};
+use crate::Game;
+
+use super::{Overlay, Screensaver, Theme};
+
+// Session-level state the app keeps between frames and hands to the renderer.
+pub struct Hud {
+    pub overlay: Overlay,
+    // What a popup opened over another screen (quit prompt, high scores) returns to.
+    pub behind: Overlay,
+    pub session_best: u64,
+    // Saved bests for every mode; the HUD shows the one being played.
+    pub bests: PersonalBests,
+    pub high_scores: Vec<ScoreEntry>,
+    pub longest_waits: Vec<WaitEntry>,
+    pub screensaver: Screensaver,
+    pub theme: Theme,
+    pub clock: SessionClock,
+    pub commands: CommandLog,
+    // Games already restarted away this session.
+    pub totals: SessionTotals,
+    pub debug: bool,
+    pub legend: bool,
+    // Per-command contributions replace the controls box, like the legend does.
+    pub leaderboard: bool,
+    pub frames: FrameStats,
+    // Game left behind by the last session, until the player resumes or discards it.
+    pub resume: Option<Game>,
+    // The focus-guard autopilot is placing pieces.
+    pub autopilot: bool,
+    // Nothing has happened for a while; draw everything dim.
+    pub dimmed: bool,
+    // Shown as the status while a recorded game plays back.
+    pub playback: Option<&'static str>,
+    // The game-over summary went to the clipboard.
+    pub shared: bool,
+    // `m` silences the bell cues for the session.
+    pub muted: bool,
+    // The other boards in multi-board mode, in the order Tab brings them up.
+    pub boards: Vec<Thumbnail>,
+}
+
+// A board that isn't on screen, as the sidebar lists it.
+pub struct Thumbnail {
+    pub sparks: String,
+    // The command the board was opened for, or "main".
+    pub label: String,
+    pub score: u64,
+}
+
+// Loop timings for the F3 debug overlay.
+pub struct FrameStats {
+    pub render: Duration,
+    pub render_avg: Duration,
+    pub frame_interval: Duration,
+    pub events_drained: usize,
+    last_frame: Instant,
+}
+
+impl FrameStats {
+    fn new() -> Self {
+        Self {
+            render: Duration::ZERO,
+            render_avg: Duration::ZERO,
+            frame_interval: Duration::ZERO,
+            events_drained: 0,
+            last_frame: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self, render: Duration, events_drained: usize) {
+        let now = Instant::now();
+        self.frame_interval = now - self.last_frame;
+        self.last_frame = now;
+        self.render = render;
+        // Exponential moving average so spikes stay visible without flicker.
+        self.render_avg = (self.render_avg * 7 + render) / 8;
+        self.events_drained = events_drained;
+    }
+}
+
+impl Hud {
+    pub fn new(bests: PersonalBests, theme: Theme) -> Self {
+        Self {
+            overlay: Overlay::None,
+            behind: Overlay::None,
+            session_best: 0,
+            bests,
+            high_scores: Vec::new(),
+            longest_waits: Vec::new(),
+            screensaver: Screensaver::new(),
+            theme,
+            clock: SessionClock::new(),
+            commands: CommandLog::default(),
+            totals: SessionTotals::default(),
+            debug: false,
+            legend: false,
+            leaderboard: false,
+            frames: FrameStats::new(),
+            resume: None,
+            autopilot: false,
+            dimmed: false,
+            playback: None,
+            shared: false,
+            muted: false,
+            boards: Vec::new(),
+        }
+    }
+}
@@ -1,3 +1,14 @@
+mod hud;
+mod overlay;
 mod render;
+mod screensaver;
+mod share;
+mod theme;
 
-pub use render::draw_game;
+pub use hud::{Hud, Thumbnail};
+pub use overlay::{Menu, MenuChoice, Overlay, TitleMenu};
+pub use render::{draw_game, next_repaint, time_phase, WellArea};
+pub(crate) use render::format_duration;
+pub use screensaver::Screensaver;
+pub use share::share_summary;
+pub use theme::Theme;
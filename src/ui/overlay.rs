@@ -0,0 +1,662 @@
+use std::time::Duration;
+
+use ratatui::prelude::*;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap};
+
+use crate::game::{Cell, CommandLog, Mode, Origin, Profile};
+use crate::persist::{ScoreEntry, WaitEntry};
+use crate::paths::{config_dir, socket_path};
+use crate::{Game, BOARD_H, BOARD_W, BOMB_CAP, CRASH_GARBAGE, MIN_PANE_WIDTH, VARIETY_THRESH};
+
+use super::render::{format_duration, truncate};
+
+// Screens drawn on top of the well; the app loop owns which one is showing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    None,
+    Title(TitleMenu),
+    Paused(Menu),
+    Help { scroll: u16 },
+    // Every command this session, newest last; scroll is the first row shown.
+    History { scroll: usize },
+    // rank is the run's place in the high-score table, if it made it.
+    GameOver { menu: Menu, new_best: bool, rank: Option<usize> },
+    Screensaver,
+    Inspect { x: usize, y: usize },
+    ConfirmQuit(Menu),
+    // Offered at launch when the last session left a game behind.
+    Resume(Menu),
+    HighScores { rank: Option<usize> },
+    // Tab from the high scores: the longest commands ever waited on.
+    LongestWaits,
+}
+
+impl Overlay {
+    pub fn freezes_game(&self) -> bool {
+        !matches!(self, Overlay::None)
+    }
+
+    pub fn inspect() -> Self {
+        Overlay::Inspect {
+            x: BOARD_W / 2,
+            y: BOARD_H - 1,
+        }
+    }
+
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        if let Overlay::Inspect { x, y } = self {
+            *x = (*x as i32 + dx).clamp(0, BOARD_W as i32 - 1) as usize;
+            *y = (*y as i32 + dy).clamp(0, BOARD_H as i32 - 1) as usize;
+        }
+    }
+
+    // Whether the well is dimmed underneath (inspect keeps it readable).
+    pub fn dims_well(&self) -> bool {
+        matches!(
+            self,
+            Overlay::Paused(_) | Overlay::GameOver { .. } | Overlay::ConfirmQuit(_)
+        )
+    }
+
+    pub fn scroll_history(&mut self, delta: i32, len: usize) {
+        if let Overlay::History { scroll } = self {
+            let max = len.saturating_sub(1) as i32;
+            *scroll = (*scroll as i32 + delta).clamp(0, max) as usize;
+        }
+    }
+
+    pub fn scroll_help(&mut self, delta: i32) {
+        if let Overlay::Help { scroll } = self {
+            // Budget for wrapping at the narrowest supported pane.
+            let wrap_w = (MIN_PANE_WIDTH - 2) as usize;
+            let max = help_lines()
+                .iter()
+                .map(|line| line.width().div_ceil(wrap_w).max(1))
+                .sum::<usize>()
+                .saturating_sub(1) as i32;
+            *scroll = (*scroll as i32 + delta).clamp(0, max) as u16;
+        }
+    }
+}
+
+// Splash shown until the player picks a mode or the first command arrives.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TitleMenu {
+    on_profile_row: bool,
+    mode: usize,
+    profile: usize,
+}
+
+impl TitleMenu {
+    pub fn new(mode: Mode, profile: Profile) -> Self {
+        Self {
+            on_profile_row: false,
+            mode: Mode::ALL.iter().position(|m| *m == mode).unwrap_or(0),
+            profile: Profile::ALL.iter().position(|p| *p == profile).unwrap_or(0),
+        }
+    }
+
+    pub fn switch_row(&mut self) {
+        self.on_profile_row = !self.on_profile_row;
+    }
+
+    pub fn cycle(&mut self, delta: isize) {
+        let (idx, len) = if self.on_profile_row {
+            (&mut self.profile, Profile::ALL.len())
+        } else {
+            (&mut self.mode, Mode::ALL.len())
+        };
+        *idx = (*idx as isize + delta).rem_euclid(len as isize) as usize;
+    }
+
+    pub fn mode(&self) -> Mode {
+        Mode::ALL[self.mode]
+    }
+
+    pub fn profile(&self) -> Profile {
+        Profile::ALL[self.profile]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuChoice {
+    Resume,
+    Restart,
+    Quit,
+    // Leave the screen but keep scoring the in-flight commands until they finish.
+    Detach,
+    HighScores,
+}
+
+const PAUSE_CHOICES: &[(MenuChoice, &str)] = &[
+    (MenuChoice::Resume, "RESUME"),
+    (MenuChoice::Restart, "RESTART"),
+    (MenuChoice::Quit, "QUIT"),
+];
+
+const CONFIRM_QUIT_CHOICES: &[(MenuChoice, &str)] = &[
+    (MenuChoice::Resume, "CANCEL"),
+    (MenuChoice::Detach, "KEEP LISTENING"),
+    (MenuChoice::Quit, "QUIT"),
+];
+
+const RESUME_CHOICES: &[(MenuChoice, &str)] =
+    &[(MenuChoice::Resume, "RESUME"), (MenuChoice::Restart, "NEW GAME")];
+
+const GAME_OVER_CHOICES: &[(MenuChoice, &str)] = &[
+    (MenuChoice::Restart, "RESTART"),
+    (MenuChoice::HighScores, "SCORES"),
+    (MenuChoice::Quit, "QUIT"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Menu {
+    choices: &'static [(MenuChoice, &'static str)],
+    selected: usize,
+}
+
+impl Menu {
+    pub fn pause() -> Self {
+        Self {
+            choices: PAUSE_CHOICES,
+            selected: 0,
+        }
+    }
+
+    pub fn game_over() -> Self {
+        Self {
+            choices: GAME_OVER_CHOICES,
+            selected: 0,
+        }
+    }
+
+    pub fn confirm_quit() -> Self {
+        Self {
+            choices: CONFIRM_QUIT_CHOICES,
+            selected: 0,
+        }
+    }
+
+    pub fn resume() -> Self {
+        Self {
+            choices: RESUME_CHOICES,
+            selected: 0,
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + self.choices.len() - 1) % self.choices.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % self.choices.len();
+    }
+
+    pub fn choice(&self) -> MenuChoice {
+        self.choices[self.selected].0
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        self.choices
+            .iter()
+            .enumerate()
+            .map(|(i, (_, label))| {
+                if i == self.selected {
+                    Line::styled(
+                        format!("> {label} <"),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    )
+                } else {
+                    Line::raw(label.to_string())
+                }
+            })
+            .collect()
+    }
+}
+
+pub(super) fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub(super) fn draw_title(frame: &mut Frame, menu: &TitleMenu, area: Rect) {
+    let selector = |selected: bool, value: &str| {
+        let style = if selected {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        Line::styled(format!("◂ {value} ▸"), style)
+    };
+    let lines = vec![
+        Line::raw(""),
+        Line::styled(
+            "W A I T R I S",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::styled(
+            "stack while you wait",
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        Line::raw(""),
+        Line::raw("MODE"),
+        selector(!menu.on_profile_row, menu.mode().name()),
+        Line::styled(
+            menu.mode().blurb(),
+            Style::default().add_modifier(Modifier::ITALIC),
+        ),
+        Line::raw(""),
+        Line::raw("PROFILE"),
+        selector(menu.on_profile_row, menu.profile().name()),
+        Line::raw(""),
+        Line::raw("↑/↓ row  ←/→ pick"),
+        Line::raw("enter start  h scores"),
+        Line::raw("q quit"),
+        Line::raw(""),
+        Line::styled(
+            "(starts on your next command)",
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ];
+    let title = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .border_type(BorderType::Thick)
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(Clear, area);
+    frame.render_widget(title, area);
+}
+
+pub(super) fn draw_pause(frame: &mut Frame, menu: &Menu, area: Rect) {
+    let mut lines = vec![Line::raw("")];
+    lines.extend(menu.lines());
+    lines.push(Line::raw(""));
+    lines.push(Line::raw("↑/↓ enter  p resume"));
+
+    let popup = centered_rect(area, 22, lines.len() as u16 + 2);
+    let overlay = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title("PAUSED")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(overlay, popup);
+}
+
+pub(super) fn draw_confirm_quit(frame: &mut Frame, game: &Game, menu: &Menu, area: Rect) {
+    let running = game.active_run_count();
+    let plural = if running == 1 { "" } else { "s" };
+    let mut lines = vec![
+        Line::raw(format!("{running} command{plural} running")),
+        Line::raw(""),
+    ];
+    lines.extend(menu.lines());
+    lines.push(Line::raw(""));
+    lines.push(Line::raw("q again to quit"));
+
+    let popup = centered_rect(area, 24, lines.len() as u16 + 2);
+    let overlay = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title("QUIT?")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(overlay, popup);
+}
+
+pub(super) fn draw_resume(frame: &mut Frame, saved: &Game, menu: &Menu, area: Rect) {
+    let row = |label: &str, value: String| Line::raw(format!("{label:<8}{value:>8}"));
+    let mut lines = vec![
+        row("MODE", saved.mode.name().to_string()),
+        row("SCORE", saved.score.to_string()),
+        row("LINES", saved.lines_cleared.to_string()),
+        row("BOMBS", saved.bombs.to_string()),
+        Line::raw(""),
+    ];
+    lines.extend(menu.lines());
+
+    let popup = centered_rect(area, 22, lines.len() as u16 + 2);
+    let overlay = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title("LAST RUN")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(overlay, popup);
+}
+
+pub(super) fn draw_game_over(
+    frame: &mut Frame,
+    game: &Game,
+    menu: &Menu,
+    new_best: bool,
+    rank: Option<usize>,
+    shared: bool,
+    area: Rect,
+) {
+    let stats = &game.stats;
+    let inner_w = area.width.saturating_sub(2) as usize;
+    let row = |label: &str, value: String| Line::raw(format!("{label:<8}{value:>8}"));
+
+    let mut lines = Vec::new();
+    if game.goal_reached() {
+        lines.push(Line::styled(
+            "COMPLETE",
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if new_best {
+        lines.push(Line::styled(
+            "★ NEW BEST ★",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK),
+        ));
+    }
+    if let Some(rank) = rank {
+        lines.push(row("RANK", format!("#{}", rank + 1)));
+    }
+    lines.push(row("MODE", game.mode.name().to_string()));
+    if game.mode.self_feeding() {
+        lines.push(row("TIME", format_duration(game.play_time())));
+    }
+    lines.push(row("SCORE", game.score.to_string()));
+    lines.push(row("LINES", game.lines_cleared.to_string()));
+    lines.push(row("BOMBS", stats.bombs_used.to_string()));
+    lines.push(row("CMDS", stats.commands_played.to_string()));
+    if let Some((command, elapsed)) = &stats.longest_command {
+        lines.push(row("LONGEST", format_duration(*elapsed)));
+        lines.push(Line::styled(
+            truncate(command, inner_w),
+            Style::default().add_modifier(Modifier::ITALIC),
+        ));
+    }
+    lines.push(row("SEED", game.seed.to_string()));
+    lines.push(Line::raw(""));
+    lines.extend(menu.lines());
+    lines.push(Line::styled(
+        if shared { "copied ✓" } else { "s share" },
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    let popup = centered_rect(area, 22, lines.len() as u16 + 2);
+    let overlay = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title("GAME OVER")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(overlay, popup);
+}
+
+pub(super) fn draw_high_scores(
+    frame: &mut Frame,
+    entries: &[ScoreEntry],
+    rank: Option<usize>,
+    area: Rect,
+) {
+    let inner_w = area.width.saturating_sub(2) as usize;
+    // Each entry gets a recap line underneath when the pane is tall enough.
+    let detailed = area.height as usize >= entries.len() * 2 + 4;
+    let mut lines = Vec::new();
+    if entries.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            "no scores yet",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let style = if rank == Some(i) {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let text = format!(
+            "{:>2}{}{:>6} {:>4} {}",
+            i + 1,
+            if entry.flagged {
+                '!'
+            } else if entry.imported {
+                '*'
+            } else {
+                ' '
+            },
+            entry.score,
+            entry.lines,
+            entry.mode.name()
+        );
+        let style = if entry.flagged {
+            style.fg(Color::Red)
+        } else {
+            style
+        };
+        lines.push(Line::styled(truncate(&text, inner_w), style));
+        if detailed {
+            let recap = format!("   {} {}", entry.date, entry.summary);
+            lines.push(Line::styled(
+                truncate(&recap, inner_w),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+    }
+    lines.push(Line::raw(""));
+    if entries.iter().any(|e| e.flagged) {
+        lines.push(Line::styled(
+            truncate("! edited outside the game", inner_w),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if entries.iter().any(|e| e.imported && !e.flagged) {
+        lines.push(Line::styled(
+            truncate("* imported, not checked", inner_w),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    lines.push(Line::raw("tab waits  h / esc close"));
+
+    let table = Paragraph::new(lines).block(
+        Block::default()
+            .title("HIGH SCORES")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+pub(super) fn draw_longest_waits(frame: &mut Frame, entries: &[WaitEntry], area: Rect) {
+    let inner_w = area.width.saturating_sub(2) as usize;
+    let detailed = area.height as usize >= entries.len() * 2 + 4;
+    let mut lines = Vec::new();
+    if entries.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            "no waits yet",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let style = if entry.exit_code == 0 {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let wait = format_duration(Duration::from_secs_f64(entry.secs));
+        let text = format!("{:>2} {:>6} {}", i + 1, wait, entry.command);
+        lines.push(Line::styled(truncate(&text, inner_w), style));
+        if detailed {
+            let recap = format!("   {} {}", entry.date, entry.recap);
+            lines.push(Line::styled(
+                truncate(&recap, inner_w),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::raw("tab scores  h / esc close"));
+
+    let table = Paragraph::new(lines).block(
+        Block::default()
+            .title("LONGEST WAITS")
+            .title_alignment(Alignment::Center)
+            .border_type(BorderType::Double)
+            .borders(Borders::ALL),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+fn help_lines() -> Vec<Line<'static>> {
+    let heading = |text: &'static str| Line::styled(text, Style::default().add_modifier(Modifier::BOLD));
+    vec![
+        heading("KEYS"),
+        Line::raw("←/→      move piece"),
+        Line::raw("↓        soft drop"),
+        Line::raw("↑        rotate"),
+        Line::raw("space    hard drop"),
+        Line::raw("i        inspect cells"),
+        Line::raw("l        color legend"),
+        Line::raw("c        command leaderboard"),
+        Line::raw("h        high scores (tab: longest waits)"),
+        Line::raw("H        command history"),
+        Line::raw("p / esc  pause menu"),
+        Line::raw("?        this help"),
+        Line::raw("m        mute sounds (STACK_BELL)"),
+        Line::raw("tab      next board (STACK_BOARDS=multi)"),
+        Line::raw("F3       debug stats"),
+        Line::raw("q        quit (confirms while commands run)"),
+        Line::raw("C-Space  other pane"),
+        Line::raw(""),
+        heading("PIECES"),
+        Line::raw("Every shell command you start is split into 8-char chunks; each chunk becomes a piece carrying that text. While the command keeps running its chunks come round again, one every few seconds of play, so a long build deals more than a quick one."),
+        Line::raw(""),
+        heading("VARIETY + BOMBS"),
+        Line::raw(format!(
+            "Running a different command than the last one fills the VARIETY meter (streaks fill it faster, repeats drain it). Each {VARIETY_THRESH} points earns a bomb, up to {BOMB_CAP}."
+        )),
+        Line::raw("Bombs drop when no command pieces are queued and clear a 3x3 area around every cell they land on."),
+        Line::raw(""),
+        heading("FAILURES"),
+        Line::raw("A command that exits non-zero pushes a garbage row (#) up from the floor and infects up to five locked cells (?)."),
+        Line::raw(format!("Ctrl-C (exit 130) only cancels the command's queued pieces. A crash (SIGSEGV and friends) pushes up {CRASH_GARBAGE} rows.")),
+        Line::raw(""),
+        heading("SOCKET PROTOCOL"),
+        Line::raw(format!("The shell hook writes lines to {}:", socket_path().display())),
+        Line::raw("  START <id> <command>"),
+        Line::raw("  OUTPUT <id> <last line>"),
+        Line::raw("  END <id> <exit code>"),
+        Line::raw(format!(
+            "`waitris install-hook` puts the hook in {}.",
+            config_dir().map_or("~/.config/waitris".to_string(), |dir| dir.display().to_string())
+        )),
+        Line::raw(""),
+        Line::raw("↑/↓ scroll   ? / esc close"),
+    ]
+}
+
+pub(super) fn draw_help(frame: &mut Frame, scroll: u16, area: Rect) {
+    let help = Paragraph::new(help_lines())
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(
+            Block::default()
+                .title("HELP")
+                .border_type(BorderType::Double)
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(Clear, area);
+    frame.render_widget(help, area);
+}
+
+pub(super) fn draw_history(frame: &mut Frame, game: &Game, log: &CommandLog, scroll: usize, area: Rect) {
+    let block = Block::default()
+        .title(format!("HISTORY ({})", log.len()))
+        .border_type(BorderType::Double)
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let header = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::styled(
+        truncate("  START   TOOK EXIT  PCS COMMAND", inner.width as usize),
+        header,
+    )];
+    if log.is_empty() {
+        lines.push(Line::styled(
+            "no commands yet",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+    let rows = (inner.height as usize).saturating_sub(2);
+    for record in log.records().iter().skip(scroll).take(rows) {
+        let (exit, pieces, style) = match record.exit_code {
+            None => (
+                "run".to_string(),
+                game.pieces_for_run(record.id),
+                Style::default().fg(Color::Yellow),
+            ),
+            Some(0) => ("0".to_string(), record.pieces, Style::default()),
+            Some(code) => (code.to_string(), record.pieces, Style::default().fg(Color::Red)),
+        };
+        let text = format!(
+            "{:>7} {:>6} {:>4} {:>4} {}",
+            format_duration(Duration::from_secs_f64(record.started_secs)),
+            format_duration(Duration::from_secs_f64(record.duration_secs)),
+            exit,
+            pieces,
+            record.command
+        );
+        lines.push(Line::styled(truncate(&text, inner.width as usize), style));
+    }
+    let footer = Rect {
+        y: inner.y + inner.height.saturating_sub(1),
+        height: inner.height.min(1),
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(lines), inner);
+    frame.render_widget(
+        Paragraph::new("↑/↓ scroll  H / esc close").style(Style::default().add_modifier(Modifier::DIM)),
+        footer,
+    );
+}
+
+// Describe the cell under the inspect cursor: which command and chunk it was cut from.
+pub(super) fn inspect_caption(game: &Game, x: usize, y: usize) -> String {
+    match game.board.get(x, y) {
+        Cell::Empty => "empty".to_string(),
+        Cell::Filled(_, _, Origin::Command { run, chunk }) => {
+            let command = game.command_for_run(run).unwrap_or("?");
+            let text = game.chunk_for_run(run, chunk).unwrap_or("");
+            format!("#{run} chunk {} \"{}\" of {command}", chunk + 1, text.trim_end_matches('░'))
+        }
+        Cell::Filled(_, _, Origin::Result { run }) => {
+            let output = game.output_for_run(run).unwrap_or("?");
+            format!("#{run} result \"{output}\"")
+        }
+        Cell::Filled(_, _, Origin::Filler) => "filler piece".to_string(),
+        Cell::Filled(_, _, Origin::Bomb) => "bomb".to_string(),
+        Cell::Filled(_, _, Origin::Garbage) => "garbage from a failed command".to_string(),
+        Cell::Filled(_, _, Origin::Infected) => "infected by a failed command".to_string(),
+    }
+}
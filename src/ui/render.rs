@@ -1,13 +1,41 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use ratatui::prelude::*;
 use ratatui::text::Line;
-use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Borders, Clear, Gauge, Paragraph};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::{Game, BOARD_H, BOARD_W, CELL_W, MIN_PANE_WIDTH, PLAY_H, PLAY_W};
-use crate::game::Cell;
+use crate::{Game, BOARD_H, BOARD_W, CELL_W, MAX_CELL_W, MIN_PANE_HEIGHT, MIN_PANE_WIDTH, PLAY_H};
+use crate::game::{Cell, Contribution, Mode, Origin};
 
-pub fn draw_game(frame: &mut Frame, game: &Game) {
+use super::overlay::{
+    draw_confirm_quit, draw_game_over, draw_help, draw_high_scores, draw_history, draw_longest_waits, draw_pause, draw_resume,
+    draw_title, inspect_caption, Overlay,
+};
+use super::screensaver::draw_screensaver;
+use super::{Hud, Theme};
+
+// Where the well landed on screen, so pointer input can be mapped back to board columns.
+#[derive(Clone, Copy, Default)]
+pub struct WellArea {
+    rect: Rect,
+    cell_w: u16,
+}
+
+impl WellArea {
+    pub fn column_at(&self, x: u16, y: u16) -> Option<usize> {
+        let inner_x = self.rect.x + 1;
+        let inner_w = self.cell_w * BOARD_W as u16;
+        let in_rows = y > self.rect.y && y < self.rect.y + self.rect.height;
+        if !in_rows || self.cell_w == 0 || x < inner_x || x >= inner_x + inner_w {
+            return None;
+        }
+        Some(((x - inner_x) / self.cell_w) as usize)
+    }
+}
+
+pub fn draw_game(frame: &mut Frame, game: &Game, hud: &Hud) -> WellArea {
     let area = frame.size();
 
     if area.width < MIN_PANE_WIDTH {
@@ -15,20 +43,28 @@ pub fn draw_game(frame: &mut Frame, game: &Game) {
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL).title("STACK"));
         frame.render_widget(msg, area);
-        return;
+        return WellArea::default();
+    }
+    if area.height < MIN_PANE_HEIGHT {
+        let msg = Paragraph::new(format!("RESIZE PANE (min height: {})", MIN_PANE_HEIGHT))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title("STACK"));
+        frame.render_widget(msg, area);
+        return WellArea::default();
     }
 
     // Outer "cabinet" frame.
     let cabinet = Block::default()
-        .title("WAITRIS")
-        .border_type(BorderType::Thick)
+        .title(hud.theme.title.as_str())
+        .border_type(hud.theme.cabinet)
         .borders(Borders::ALL)
         .title_alignment(Alignment::Left);
     let cabinet_inner = cabinet.inner(area);
     frame.render_widget(cabinet, area);
 
-    let well_w = PLAY_W as u16;
-    let well_h = PLAY_H as u16;
+    let well = WellGeometry::fit(cabinet_inner);
+    let well_w = well.play_w as u16;
+    let well_h = well.play_h as u16;
 
     let col_rect = Layout::default()
         .direction(Direction::Horizontal)
@@ -39,14 +75,17 @@ pub fn draw_game(frame: &mut Frame, game: &Game) {
         ])
         .split(cabinet_inner)[1];
 
-    let info_h = 5u16;
-    let controls_h = 5u16;
+    // Short panes drop the controls box first, then INFO, so the well is never squeezed.
+    let spare = cabinet_inner.height.saturating_sub(well_h + 1);
+    let info_h = if spare >= 7 { 7u16 } else { 0 };
+    let controls_h = if spare >= 7 + 6 { 6u16 } else { 0 };
     let stack = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(0),
             Constraint::Length(info_h),
             Constraint::Length(well_h),
+            Constraint::Length(1),
             Constraint::Length(controls_h),
             Constraint::Min(0),
         ])
@@ -54,7 +93,8 @@ pub fn draw_game(frame: &mut Frame, game: &Game) {
 
     let mut info_rect = stack[1];
     let well_rect = stack[2];
-    let mut controls_rect = stack[3];
+    let marquee_rect = stack[3];
+    let mut controls_rect = stack[4];
     // Widen info/controls boxes slightly while keeping them centered in the cabinet.
     let expand = 8u16;
     let max_right = cabinet_inner.x + cabinet_inner.width;
@@ -68,89 +108,205 @@ pub fn draw_game(frame: &mut Frame, game: &Game) {
     controls_rect.x = new_x;
     controls_rect.width = new_w;
 
-    draw_info(frame, game, info_rect);
-    draw_playfield(frame, game, well_rect);
-    draw_controls(frame, controls_rect);
+    draw_info(frame, game, hud, info_rect);
+    draw_playfield(frame, game, hud, &well, well_rect);
+    if let Overlay::Inspect { x, y } = hud.overlay {
+        let caption = Paragraph::new(inspect_caption(game, x, y))
+            .alignment(Alignment::Center)
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        frame.render_widget(caption, marquee_rect);
+    } else {
+        draw_marquee(frame, game, marquee_rect);
+    }
+    if hud.legend {
+        draw_legend(frame, game, hud, controls_rect);
+    } else if hud.leaderboard {
+        draw_leaderboard(frame, game, hud, controls_rect);
+    } else if !hud.boards.is_empty() {
+        draw_boards(frame, hud, controls_rect);
+    } else {
+        draw_controls(frame, controls_rect);
+    }
+
+    match &hud.overlay {
+        Overlay::None => {}
+        Overlay::Title(menu) => draw_title(frame, menu, area),
+        Overlay::Paused(menu) => draw_pause(frame, menu, well_rect),
+        Overlay::Help { scroll } => draw_help(frame, *scroll, area),
+        Overlay::History { scroll } => draw_history(frame, game, &hud.commands, *scroll, area),
+        Overlay::Screensaver => draw_screensaver(frame, &hud.screensaver, area),
+        Overlay::Inspect { .. } => {}
+        Overlay::ConfirmQuit(menu) => draw_confirm_quit(frame, game, menu, well_rect),
+        Overlay::Resume(menu) => {
+            if let Some(saved) = &hud.resume {
+                draw_resume(frame, saved, menu, well_rect);
+            }
+        }
+        Overlay::GameOver {
+            menu,
+            new_best,
+            rank,
+        } => draw_game_over(frame, game, menu, *new_best, *rank, hud.shared, well_rect),
+        Overlay::HighScores { rank } => draw_high_scores(frame, &hud.high_scores, *rank, area),
+        Overlay::LongestWaits => draw_longest_waits(frame, &hud.longest_waits, area),
+    }
+
+    if hud.debug {
+        draw_debug(frame, game, hud, area);
+    }
+    if hud.dimmed && hud.overlay != Overlay::Screensaver {
+        frame
+            .buffer_mut()
+            .set_style(area, Style::default().add_modifier(Modifier::DIM));
+    }
+
+    WellArea {
+        rect: well_rect,
+        cell_w: well.cell_w as u16,
+    }
 }
 
-fn draw_playfield(frame: &mut Frame, game: &Game, play_rect: Rect) {
-    let mut grid = vec![vec![' '; PLAY_W]; PLAY_H];
+// Well dimensions for the current frame; cells widen when the pane has room to spare.
+struct WellGeometry {
+    cell_w: usize,
+    play_w: usize,
+    play_h: usize,
+}
 
-    // Border: top/ceiling, sides, heavy floor.
-    grid[0][0] = '┌';
-    grid[0][PLAY_W - 1] = '┐';
-    for x in 1..PLAY_W - 1 {
-        grid[0][x] = '─';
+impl WellGeometry {
+    fn with_cell_w(cell_w: usize) -> Self {
+        Self {
+            cell_w,
+            play_w: BOARD_W * cell_w + 2,
+            play_h: PLAY_H,
+        }
     }
-    for y in 1..PLAY_H - 1 {
-        grid[y][0] = '│';
-        grid[y][PLAY_W - 1] = '│';
+
+    fn fit(area: Rect) -> Self {
+        (CELL_W..=MAX_CELL_W)
+            .rev()
+            .map(Self::with_cell_w)
+            .find(|g| g.play_w as u16 <= area.width)
+            .unwrap_or_else(|| Self::with_cell_w(CELL_W))
     }
-    grid[PLAY_H - 1][0] = '└';
-    grid[PLAY_H - 1][PLAY_W - 1] = '┘';
-    for x in 1..PLAY_W - 1 {
-        grid[PLAY_H - 1][x] = '═';
+
+    fn cell_x(&self, bx: usize) -> usize {
+        1 + bx * self.cell_w
+    }
+}
+
+// Stand-in for payload chars that don't occupy exactly one column (CJK, emoji, combining marks).
+const WIDE_GLYPH: char = '*';
+
+// The well grid is one char per terminal column, so anything wider or narrower would shear it.
+fn narrow(ch: char) -> char {
+    if ch.width() == Some(1) { ch } else { WIDE_GLYPH }
+}
+
+// Expand a `letter + filler` pair to the cell width. Solid glyphs repeat; payload pads with filler.
+fn cell_glyphs(left: char, right: char, cell_w: usize) -> impl Iterator<Item = char> {
+    let (left, right) = (narrow(left), narrow(right));
+    let pad = if left == right { right } else { '░' };
+    [left, right]
+        .into_iter()
+        .chain(std::iter::repeat(pad))
+        .take(cell_w)
+}
+
+// One terminal column of the well: a glyph plus the style it renders with.
+type StyledCell = (char, Style);
+
+fn draw_playfield(frame: &mut Frame, game: &Game, hud: &Hud, well: &WellGeometry, play_rect: Rect) {
+    let (play_w, play_h) = (well.play_w, well.play_h);
+    let theme = &hud.theme;
+    let mut grid: Vec<Vec<StyledCell>> = vec![vec![(' ', Style::default()); play_w]; play_h];
+
+    // Border: top/ceiling, sides, heavy floor.
+    let border = &theme.well;
+    let fill = |row: &mut [StyledCell], ch: char| row.iter_mut().for_each(|c| c.0 = ch);
+    grid[0][0].0 = border.top_left;
+    grid[0][play_w - 1].0 = border.top_right;
+    fill(&mut grid[0][1..play_w - 1], border.ceiling);
+    for row in grid.iter_mut().take(play_h - 1).skip(1) {
+        row[0].0 = border.wall;
+        row[play_w - 1].0 = border.wall;
     }
+    grid[play_h - 1][0].0 = border.bottom_left;
+    grid[play_h - 1][play_w - 1].0 = border.bottom_right;
+    fill(&mut grid[play_h - 1][1..play_w - 1], border.floor);
 
     // Helper to plot a filled block in the inner area. Draw as `letter + light filler`.
-    let plot_block = |grid: &mut [Vec<char>], bx: usize, by: usize, left: char, right: char| {
-        let gx = 1 + bx * CELL_W;
+    let plot_block = |grid: &mut [Vec<StyledCell>],
+                      bx: usize,
+                      by: usize,
+                      left: char,
+                      right: char,
+                      style: Style| {
+        let gx = well.cell_x(bx);
         let gy = 1 + by;
-        if gy < PLAY_H && gx + 1 < PLAY_W {
-            grid[gy][gx] = left;
-            grid[gy][gx + 1] = right;
+        if gy < play_h && gx + well.cell_w < play_w {
+            for (i, ch) in cell_glyphs(left, right, well.cell_w).enumerate() {
+                grid[gy][gx + i] = (ch, style);
+            }
         }
     };
-
-    // Locked cells (with optional lock flash override).
-    for y in 0..game.board.height {
-        for x in 0..game.board.width {
-            if let Cell::Filled(left_ch, right_ch) = game.board.get(x, y) {
-                let flashing = game.lock_flash_frames > 0
-                    && game.lock_flash_cells.contains(&(x, y));
-                let left = if flashing { '▓' } else { left_ch };
-                let right = if flashing { '▓' } else { right_ch };
-                plot_block(&mut grid, x, y, left, right);
+    // Write text over a border row, centered, clipped to the inner width.
+    let banner = |row: &mut [StyledCell], text: &str, style: Style| {
+        let text: Vec<char> = text.chars().collect();
+        let start = (play_w.saturating_sub(text.len()) / 2).max(1);
+        for (i, ch) in text.into_iter().enumerate() {
+            if start + i < play_w - 1 {
+                row[start + i] = (ch, style);
             }
         }
+    };
+
+    // Locked cells (with optional lock flash override). Penalty cells get their own styles.
+    for (x, y, cell) in game.board.iter_filled() {
+        let Cell::Filled(left_ch, right_ch, origin) = cell else {
+            continue;
+        };
+        let flashing = game.lock_flash_frames > 0 && game.lock_flash_cells.contains(&(x, y));
+        let left = if flashing { '▓' } else { left_ch };
+        let right = if flashing { '▓' } else { right_ch };
+        let style = match origin {
+            Origin::Garbage => theme.garbage,
+            Origin::Infected => theme.infected,
+            _ => payload_style(game, theme, origin),
+        };
+        plot_block(&mut grid, x, y, left, right, style);
     }
 
-    if game.active_piece {
-        if game.current_is_bomb {
-            // Bomb drop banner along the top inside the well.
-            let banner = " BOMB INBOUND ";
-            let start = ((PLAY_W as i32 - banner.len() as i32) / 2).max(1) as usize;
-            let gy = 0;
-            for (i, ch) in banner.chars().enumerate() {
-                if start + i < PLAY_W - 1 {
-                    grid[gy][start + i] = ch;
-                }
-            }
-        }
+    let danger = game.in_danger();
+    if danger {
+        let style = if blink_on() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        grid[0].iter_mut().for_each(|c| c.1 = style);
+    }
+    let ceiling_style = grid[0][0].1;
+    if game.active_piece && game.current_is_bomb {
+        banner(&mut grid[0], " BOMB INBOUND ", ceiling_style);
+    } else if danger {
+        banner(&mut grid[0], " DANGER ", ceiling_style);
+    }
 
+    if game.active_piece {
         // Ghost piece: draw with faint glyphs.
         let ghost = game.ghost_piece();
         for (x, y, _) in ghost.cells() {
-            if x >= 0 && y >= 0 {
-                let (xu, yu) = (x as usize, y as usize);
-                if xu < game.board.width && yu < game.board.height {
-                    let gx = 1 + xu * CELL_W;
-                    let gy = 1 + yu;
-                    if gy < PLAY_H && gx + 1 < PLAY_W {
-                        grid[gy][gx] = '·';
-                        grid[gy][gx + 1] = '·';
-                    }
-                }
+            if game.board.contains(x, y) {
+                plot_block(&mut grid, x as usize, y as usize, '·', '·', Style::default());
             }
         }
 
         // Active piece.
+        let style = payload_style(game, theme, game.current.origin);
         for (x, y, (left, right)) in game.current.cells_with_pairs() {
-            if x >= 0 && y >= 0 {
-                let (xu, yu) = (x as usize, y as usize);
-                if xu < game.board.width && yu < game.board.height {
-                    plot_block(&mut grid, xu, yu, left, right);
-                }
+            if game.board.contains(x, y) {
+                plot_block(&mut grid, x as usize, y as usize, left, right, style);
             }
         }
     }
@@ -159,52 +315,207 @@ fn draw_playfield(frame: &mut Frame, game: &Game, play_rect: Rect) {
     if game.clear_flash_frames > 0 && !game.pending_clear.is_empty() {
         for &row in &game.pending_clear {
             if row < BOARD_H {
-                let gy = 1 + row;
                 for x in 0..BOARD_W {
-                    let gx = 1 + x * CELL_W;
-                    if gy < PLAY_H && gx + 1 < PLAY_W {
-                        grid[gy][gx] = '█';
-                        grid[gy][gx + 1] = '█';
-                    }
+                    plot_block(&mut grid, x, row, '█', '█', Style::default());
                 }
             }
         }
     }
 
-    let lines: Vec<Line> = grid
-        .iter()
-        .map(|row| Line::raw(row.iter().collect::<String>()))
-        .collect();
+    // Combo banner on the floor: live while the streak holds, fading out once it breaks.
+    let combo = if game.combo >= 2 {
+        Some((game.combo, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+    } else if game.combo_fade > 0 && game.last_combo >= 2 {
+        let style = match game.combo_fade {
+            9.. => Style::default().fg(Color::Cyan),
+            5..=8 => Style::default(),
+            _ => Style::default().add_modifier(Modifier::DIM),
+        };
+        Some((game.last_combo, style))
+    } else {
+        None
+    };
+    if let Some((count, style)) = combo {
+        banner(&mut grid[play_h - 1], &format!(" COMBO x{count} "), style);
+    }
 
-    let paragraph = Paragraph::new(lines).alignment(Alignment::Left);
+    if let Overlay::Inspect { x, y } = hud.overlay {
+        let gx = well.cell_x(x);
+        for cell in &mut grid[1 + y][gx..gx + well.cell_w] {
+            cell.1 = cell.1.add_modifier(Modifier::REVERSED);
+        }
+    }
+
+    let lines: Vec<Line> = grid.iter().map(|row| styled_line(row)).collect();
+    let mut paragraph = Paragraph::new(lines).alignment(Alignment::Left);
+    if hud.overlay.dims_well() {
+        paragraph = paragraph.style(Style::default().add_modifier(Modifier::DIM));
+    }
     frame.render_widget(paragraph, play_rect);
+}
 
-    if game.game_over {
-        let overlay_w = (PLAY_W as u16).saturating_sub(4).max(8);
-        let overlay_h = 5u16;
-        let popup = Rect {
-            x: play_rect.x + (play_rect.width.saturating_sub(overlay_w)) / 2,
-            y: play_rect.y + (play_rect.height.saturating_sub(overlay_h)) / 2,
-            width: overlay_w,
-            height: overlay_h,
-        };
-        let overlay = Paragraph::new("GAME OVER\nPress q")
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(overlay, popup);
+fn payload_style(game: &Game, theme: &Theme, origin: Origin) -> Style {
+    match origin {
+        Origin::Command { run, .. } => {
+            let identity = game.identity_for_run(run).unwrap_or_default();
+            Style::default().fg(theme.payload_color(identity))
+        }
+        Origin::Result { run } => {
+            let identity = game.identity_for_run(run).unwrap_or_default();
+            Style::default().fg(theme.payload_color(identity)).add_modifier(Modifier::REVERSED)
+        }
+        _ => Style::default(),
+    }
+}
+
+// Merge runs of equally styled cells into spans.
+fn styled_line(row: &[StyledCell]) -> Line<'static> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut text = String::new();
+    let mut current = row.first().map(|c| c.1).unwrap_or_default();
+    for &(ch, style) in row {
+        if style != current {
+            spans.push(Span::styled(std::mem::take(&mut text), current));
+            current = style;
+        }
+        text.push(ch);
     }
+    spans.push(Span::styled(text, current));
+    Line::from(spans)
 }
 
-fn draw_info(frame: &mut Frame, game: &Game, area: Rect) {
+pub(crate) fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+// Widths are terminal columns, not chars, so wide payloads don't overrun their box.
+pub(super) fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let mut out = take_width(text.chars(), max.saturating_sub(1));
+    out.push('…');
+    out
+}
+
+fn take_width(chars: impl Iterator<Item = char>, max: usize) -> String {
+    let mut used = 0;
+    chars
+        .take_while(|ch| {
+            used += ch.width().unwrap_or(0);
+            used <= max
+        })
+        .collect()
+}
+
+const BLINK_MS: u128 = 300;
+const MARQUEE_STEP_MS: u128 = 200;
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn blink_on() -> bool {
+    (now_millis() / BLINK_MS).is_multiple_of(2)
+}
+
+// Time until the next wall-clock phase boundary of `period_ms`.
+fn until_phase(period_ms: u128) -> Duration {
+    Duration::from_millis((period_ms - now_millis() % period_ms) as u64)
+}
+
+// Changes whenever time-driven content would look different: blink phase, marquee step,
+// or the seconds shown by the clocks.
+pub fn time_phase(game: &Game, hud: &Hud) -> (u128, u128, u128) {
+    let now = now_millis();
+    let marquee = if game.current_command().is_some() { now / MARQUEE_STEP_MS } else { 0 };
+    let blink = if next_repaint(game, hud).is_some() { now / BLINK_MS } else { 0 };
+    (marquee, blink, now / 1000)
+}
+
+// When the screen next changes without any input: blinking text or the scrolling marquee.
+// None means the current frame stays valid until something happens.
+pub fn next_repaint(game: &Game, hud: &Hud) -> Option<Duration> {
+    if hud.overlay.freezes_game() && !matches!(hud.overlay, Overlay::Inspect { .. }) {
+        return None;
+    }
+    if game.current_command().is_some() {
+        return Some(until_phase(MARQUEE_STEP_MS));
+    }
+    let blinking = game.is_running()
+        || game.in_danger()
+        || game.level_up_frames > 0
+        || game.score > hud.session_best;
+    blinking.then(|| until_phase(BLINK_MS))
+}
+
+// One line naming the command behind the falling piece, scrolling when it doesn't fit.
+fn draw_marquee(frame: &mut Frame, game: &Game, area: Rect) {
+    let Some(command) = game.current_command() else {
+        return;
+    };
+    let elapsed = game.current_command_elapsed().unwrap_or_default();
+    let command = format!("{command} · {}", format_duration(elapsed));
+    let command = command.as_str();
+    let width = area.width as usize;
+    let text: String = if command.width() <= width {
+        command.to_string()
+    } else {
+        let gap = "   ".chars();
+        let looped: Vec<char> = command.chars().chain(gap).collect();
+        let offset = (now_millis() / MARQUEE_STEP_MS) as usize % looped.len();
+        take_width(looped.iter().copied().cycle().skip(offset), width)
+    };
+    let marquee = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .style(Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM));
+    frame.render_widget(marquee, area);
+}
+
+fn draw_debug(frame: &mut Frame, game: &Game, hud: &Hud, area: Rect) {
+    let ms = |d: Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+    let f = &hud.frames;
+    let lines = vec![
+        Line::raw(format!("render {}", ms(f.render))),
+        Line::raw(format!("avg    {}", ms(f.render_avg))),
+        Line::raw(format!("frame  {}", ms(f.frame_interval))),
+        Line::raw(format!("events {}", f.events_drained)),
+        Line::raw(format!("queue  {}", game.queued_pieces())),
+        Line::raw(format!("runs   {}", game.active_run_count())),
+    ];
+    let w = 18.min(area.width);
+    let h = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect {
+        x: area.x + area.width - w,
+        y: area.y,
+        width: w,
+        height: h,
+    };
+    let debug = Paragraph::new(lines)
+        .style(Style::default().fg(Color::Green))
+        .block(Block::default().title("DEBUG").borders(Borders::ALL));
+    frame.render_widget(Clear, rect);
+    frame.render_widget(debug, rect);
+}
+
+fn draw_info(frame: &mut Frame, game: &Game, hud: &Hud, area: Rect) {
     let running = game.is_running();
-    let status = if game.game_over {
+    let status = if let Some(label) = hud.playback {
+        label
+    } else if game.game_over {
         "OVER"
+    } else if hud.autopilot && game.active_piece {
+        "AUTO"
     } else if running {
-        let millis = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis();
-        if (millis / 300) % 2 == 0 {
+        if blink_on() {
             "ACTIVE"
         } else {
             "      "
@@ -213,7 +524,21 @@ fn draw_info(frame: &mut Frame, game: &Game, area: Rect) {
         "IDLE"
     };
 
-    let block = Block::default().title("INFO").borders(Borders::ALL);
+    // The seed sits in the border so a run worth repeating can be started again with --seed.
+    let seed = Title::from(Span::styled(
+        format!("seed {}", game.seed),
+        Style::default().add_modifier(Modifier::DIM),
+    ))
+    .position(Position::Bottom)
+    .alignment(Alignment::Right);
+    let mut block = Block::default().title("INFO").title(seed).borders(Borders::ALL);
+    if hud.muted {
+        block = block.title(
+            Title::from(Span::styled("muted", Style::default().add_modifier(Modifier::DIM)))
+                .position(Position::Bottom)
+                .alignment(Alignment::Left),
+        );
+    }
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -222,20 +547,175 @@ fn draw_info(frame: &mut Frame, game: &Game, area: Rect) {
         .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
         .split(inner);
 
+    // Flash the score once this run beats a previous best.
+    let best = hud.bests.get(game.mode);
+    let score_style = if game.score > best.score && blink_on() {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    } else if game.score > hud.session_best && blink_on() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
     let left = Paragraph::new(vec![
-        Line::raw(format!("{:<7} {}", "SCORE:", game.score)),
+        Line::styled(format!("{:<7} {}", "SCORE:", game.score), score_style),
         Line::raw(format!("{:<7} {}", "LINES:", game.lines_cleared)),
         Line::raw(format!("{:<7} {}", "STATUS:", status)),
+        Line::raw(format!("{:<7} {}", "BEST:", hud.session_best.max(game.score))),
+        Line::raw(format!("{:<7} {}", "TIME:", format_duration(hud.clock.session_time()))),
     ])
     .alignment(Alignment::Left);
     frame.render_widget(left, cols[0]);
 
-    let right = Paragraph::new(vec![
-        Line::raw(format!("{:<6} {}", "BOMBS:", game.bombs)),
-        Line::raw(format!("{:<6} {}", "VARIETY:", game.variety_meter)),
-    ])
-    .alignment(Alignment::Left);
-    frame.render_widget(right, cols[1]);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(cols[1]);
+    frame.render_widget(
+        Paragraph::new(format!("{:<6} {}", "BOMBS:", game.bombs)),
+        rows[0],
+    );
+    frame.render_widget(variety_gauge(game), rows[1]);
+    // Sprint is raced against the clock, so its PB is a time.
+    let personal_best = if game.mode == Mode::Sprint {
+        let time = best.time().map_or("--".to_string(), format_duration);
+        format!("{:<6} {time}", "PB:")
+    } else {
+        format!("{:<6} {}", "HI:", best.score.max(game.score))
+    };
+    frame.render_widget(Paragraph::new(personal_best), rows[2]);
+    frame.render_widget(level_line(game), rows[3]);
+    // Time spent with at least one command running: the boredom this game soaked up.
+    frame.render_widget(
+        Paragraph::new(format!("{:<6} {}", "WAIT:", format_duration(hud.clock.busy_time()))),
+        rows[4],
+    );
+}
+
+// Level plus current gravity interval, flashing briefly right after a level-up.
+fn level_line(game: &Game) -> Paragraph<'static> {
+    let ms = game.gravity_interval().as_millis();
+    if game.level_up_frames > 0 {
+        let style = if blink_on() {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+        Paragraph::new(format!("LV {} ▲{ms}ms", game.level())).style(style)
+    } else {
+        Paragraph::new(format!("LV {} {ms}ms", game.level()))
+    }
+}
+
+// Progress toward the next bomb; full once the ruleset's threshold is banked.
+fn variety_gauge(game: &Game) -> Gauge<'static> {
+    let threshold = game.bomb_threshold().max(1);
+    let meter = game.variety_meter.clamp(0, threshold);
+    let style = if game.bombs >= game.bomb_cap() {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default().fg(Color::Magenta)
+    };
+    Gauge::default()
+        .gauge_style(style)
+        .ratio(meter as f64 / threshold as f64)
+        .label(format!("VAR {meter}/{threshold}"))
+}
+
+// Swatch + command for every run currently on the board; shares the CONTROLS slot.
+fn draw_legend(frame: &mut Frame, game: &Game, hud: &Hud, area: Rect) {
+    let block = Block::default().title("LEGEND").borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let runs = game.visible_runs();
+    let rows = inner.height as usize;
+    let width = inner.width.saturating_sub(3) as usize;
+    let shown = if runs.len() > rows { rows.saturating_sub(1) } else { runs.len() };
+    let mut lines: Vec<Line> = runs
+        .iter()
+        .take(shown)
+        .map(|&run| {
+            let identity = game.identity_for_run(run).unwrap_or_default();
+            let command = game.command_for_run(run).unwrap_or_default();
+            Line::from(vec![
+                Span::styled("██", Style::default().fg(hud.theme.payload_color(identity))),
+                Span::raw(" "),
+                Span::raw(truncate(command, width)),
+            ])
+        })
+        .collect();
+    if runs.is_empty() {
+        lines.push(Line::raw("no commands on board"));
+    } else if shown < runs.len() {
+        lines.push(Line::raw(format!("+{} more", runs.len() - shown)));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_leaderboard(frame: &mut Frame, game: &Game, hud: &Hud, area: Rect) {
+    let block = Block::default().title("COMMANDS").borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = game.stats.leaderboard();
+    if rows.is_empty() {
+        frame.render_widget(Paragraph::new("no commands yet"), inner);
+        return;
+    }
+    let counts = |c: Contribution| format!("{}p {}l {}g {}b", c.pieces, c.lines, c.garbage, c.bombs);
+    let counts_width = rows.iter().map(|(_, c)| counts(*c).len()).max().unwrap_or(0);
+    let width = (inner.width as usize).saturating_sub(counts_width + 4);
+    let lines: Vec<Line> = rows
+        .iter()
+        .take(inner.height as usize)
+        .map(|&(identity, c)| {
+            Line::from(vec![
+                Span::styled("██", Style::default().fg(hud.theme.payload_color(identity))),
+                Span::raw(" "),
+                Span::raw(format!("{:<width$}", truncate(identity, width))),
+                Span::raw(" "),
+                Span::raw(format!("{:>counts_width$}", counts(c))),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+// Multi-board mode: the boards Tab cycles through, each as its column heights.
+fn draw_boards(frame: &mut Frame, hud: &Hud, area: Rect) {
+    let block = Block::default().title("BOARDS (tab)").borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = inner.height as usize;
+    let shown = if hud.boards.len() > rows { rows.saturating_sub(1) } else { hud.boards.len() };
+    let mut lines: Vec<Line> = hud
+        .boards
+        .iter()
+        .take(shown)
+        .map(|thumb| {
+            let score = thumb.score.to_string();
+            let used = thumb.sparks.width() + score.len() + 2;
+            let width = (inner.width as usize).saturating_sub(used);
+            Line::from(vec![
+                Span::styled(thumb.sparks.clone(), Style::default().add_modifier(Modifier::DIM)),
+                Span::raw(" "),
+                Span::raw(format!("{:<width$}", truncate(&thumb.label, width))),
+                Span::raw(" "),
+                Span::raw(score),
+            ])
+        })
+        .collect();
+    if shown < hud.boards.len() {
+        lines.push(Line::raw(format!("+{} more", hud.boards.len() - shown)));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
 }
 
 fn draw_controls(frame: &mut Frame, area: Rect) {
@@ -251,6 +731,7 @@ fn draw_controls(frame: &mut Frame, area: Rect) {
     let left = Paragraph::new(vec![
         Line::raw("←/→ move"),
         Line::raw("↓ soft"),
+        Line::raw("p pause"),
         Line::raw("q quit"),
     ])
     .alignment(Alignment::Left);
@@ -259,6 +740,7 @@ fn draw_controls(frame: &mut Frame, area: Rect) {
     let right = Paragraph::new(vec![
         Line::raw("↑ rotate"),
         Line::raw("space slam"),
+        Line::raw("? help"),
         Line::raw("Ctrl+Space swap"),
     ])
     .alignment(Alignment::Left);
@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use rand::Rng;
+use ratatui::prelude::*;
+use ratatui::widgets::Clear;
+use unicode_width::UnicodeWidthChar;
+
+const MAX_GLYPHS: usize = 256;
+const DROPS: usize = 24;
+const TRAIL: usize = 4;
+
+struct Drop {
+    x: f32,
+    speed: f32,
+    phase: f32,
+    seed: usize,
+}
+
+// Falling-glyph animation built from recently seen command text. Positions derive from elapsed
+// time, so drawing needs no mutable state.
+pub struct Screensaver {
+    glyphs: VecDeque<char>,
+    drops: Vec<Drop>,
+    started: Instant,
+}
+
+impl Default for Screensaver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screensaver {
+    pub fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let drops = (0..DROPS)
+            .map(|_| Drop {
+                x: rng.gen_range(0.0..1.0),
+                speed: rng.gen_range(2.0..6.0),
+                phase: rng.gen_range(0.0..40.0),
+                seed: rng.gen_range(0..MAX_GLYPHS),
+            })
+            .collect();
+        Self {
+            glyphs: "WAITRIS".chars().collect(),
+            drops,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn feed(&mut self, command: &str) {
+        self.glyphs
+            .extend(command.chars().filter(|ch| !ch.is_whitespace() && ch.width() == Some(1)));
+        while self.glyphs.len() > MAX_GLYPHS {
+            self.glyphs.pop_front();
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.started = Instant::now();
+    }
+}
+
+pub(super) fn draw_screensaver(frame: &mut Frame, saver: &Screensaver, area: Rect) {
+    frame.render_widget(Clear, area);
+    if area.width == 0 || area.height == 0 || saver.glyphs.is_empty() {
+        return;
+    }
+    let t = saver.started.elapsed().as_secs_f32();
+    let span = area.height as f32 + TRAIL as f32;
+    let buf = frame.buffer_mut();
+    for drop in &saver.drops {
+        let travelled = drop.phase + t * drop.speed;
+        let lap = (travelled / span) as usize;
+        let head = travelled % span;
+        let x = area.x + ((drop.x * area.width as f32) as u16).min(area.width - 1);
+        let ch = saver.glyphs[(drop.seed + lap * 7) % saver.glyphs.len()];
+        for i in 0..TRAIL {
+            let y = head as i32 - i as i32;
+            if y < 0 || y >= area.height as i32 {
+                continue;
+            }
+            let style = if i == 0 {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::DIM)
+            };
+            buf.get_mut(x, area.y + y as u16)
+                .set_char(ch)
+                .set_style(style);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::Game;
+
+use super::render::{format_duration, truncate};
+
+// A few lines to paste into chat after a game: the result, the biggest clear and the commands
+// that mattered.
+pub fn share_summary(game: &Game) -> String {
+    let stats = &game.stats;
+    let mut headline = format!(
+        "🧱 WAITRIS {} · {} pts · {} lines",
+        game.mode.name(),
+        game.score,
+        game.lines_cleared
+    );
+    if game.mode.self_feeding() {
+        headline.push_str(&format!(" · {}", format_duration(game.play_time())));
+    }
+    let mut lines = vec![headline];
+    if stats.biggest_clear > 0 {
+        lines.push(format!("💥 biggest clear: {}", clear_name(stats.biggest_clear)));
+    }
+    if let Some((command, elapsed)) = &stats.longest_command {
+        lines.push(format!(
+            "⏳ longest wait: {} ({})",
+            truncate(command, 40),
+            format_duration(*elapsed)
+        ));
+    }
+    if let Some((identity, c)) = stats.leaderboard().first() {
+        lines.push(format!(
+            "🏆 top command: {identity} ({} pieces, {} lines)",
+            c.pieces, c.lines
+        ));
+    }
+    lines.push(format!(
+        "💣 {} bombs · {} cmds · 🎲 seed {}",
+        stats.bombs_used, stats.commands_played, game.seed
+    ));
+    lines.join("\n")
+}
+
+fn clear_name(rows: u32) -> &'static str {
+    match rows {
+        1 => "single",
+        2 => "double",
+        3 => "triple",
+        _ => "TETRIS",
+    }
+}
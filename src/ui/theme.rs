@@ -0,0 +1,127 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::BorderType;
+
+// Glyphs for the well outline: ceiling, walls, and the heavier floor.
+#[derive(Clone, Copy)]
+pub struct WellBorder {
+    pub top_left: char,
+    pub top_right: char,
+    pub ceiling: char,
+    pub wall: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub floor: char,
+}
+
+#[derive(Clone)]
+pub struct Theme {
+    pub well: WellBorder,
+    pub cabinet: BorderType,
+    pub title: String,
+    // Penalty cells from failed commands.
+    pub garbage: Style,
+    pub infected: Style,
+    // Payload cells are tinted per command so the legend can map colors back to commands.
+    pub payload_colors: Vec<Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset("single")
+    }
+}
+
+impl Theme {
+    pub fn preset(name: &str) -> Self {
+        let (well, cabinet) = match name {
+            "double" => (
+                WellBorder {
+                    top_left: '╔',
+                    top_right: '╗',
+                    ceiling: '═',
+                    wall: '║',
+                    bottom_left: '╚',
+                    bottom_right: '╝',
+                    floor: '═',
+                },
+                BorderType::Double,
+            ),
+            "rounded" => (
+                WellBorder {
+                    top_left: '╭',
+                    top_right: '╮',
+                    ceiling: '─',
+                    wall: '│',
+                    bottom_left: '╰',
+                    bottom_right: '╯',
+                    floor: '─',
+                },
+                BorderType::Rounded,
+            ),
+            "minimal" => (
+                WellBorder {
+                    top_left: ' ',
+                    top_right: ' ',
+                    ceiling: ' ',
+                    wall: '│',
+                    bottom_left: '└',
+                    bottom_right: '┘',
+                    floor: '─',
+                },
+                BorderType::Plain,
+            ),
+            _ => (
+                WellBorder {
+                    top_left: '┌',
+                    top_right: '┐',
+                    ceiling: '─',
+                    wall: '│',
+                    bottom_left: '└',
+                    bottom_right: '┘',
+                    floor: '═',
+                },
+                BorderType::Thick,
+            ),
+        };
+        Self {
+            well,
+            cabinet,
+            title: "WAITRIS".to_string(),
+            garbage: Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM),
+            infected: Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::UNDERLINED),
+            payload_colors: vec![
+                Color::Cyan,
+                Color::Yellow,
+                Color::Green,
+                Color::Magenta,
+                Color::Blue,
+                Color::LightRed,
+                Color::LightGreen,
+                Color::LightCyan,
+            ],
+        }
+    }
+
+    // Stable color for a command identity (same program, same color).
+    pub fn payload_color(&self, identity: &str) -> Color {
+        if self.payload_colors.is_empty() {
+            return Color::Reset;
+        }
+        // FNV-1a keeps the mapping stable across runs.
+        let hash = identity
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        self.payload_colors[(hash % self.payload_colors.len() as u64) as usize]
+    }
+
+    // STACK_THEME picks a preset (single, double, rounded, minimal); STACK_TITLE renames the cabinet.
+    pub fn from_env() -> Self {
+        let mut theme = Self::preset(&std::env::var("STACK_THEME").unwrap_or_default());
+        if let Ok(title) = std::env::var("STACK_TITLE") {
+            theme.title = title;
+        }
+        theme
+    }
+}
@@ -0,0 +1,365 @@
+use std::time::Duration;
+
+use stack_game::game::{
+    Board, Cell, Feed, Guideline, ManualClock, Mode, Origin, Profile, ScoringRules, Shape,
+};
+use stack_game::testing::{assert_board, board, game, place, place_bomb, run_script};
+use stack_game::{Game, BOARD_H, BOARD_W, CRASH_GARBAGE, REPEAT_SECS};
+
+#[test]
+fn piece_locks_on_the_stack() {
+    let mut game = game(
+        "
+        ##........
+        ##........
+        ",
+    );
+    place(&mut game, Shape::O, 0, -1);
+    assert_board(
+        &game,
+        "
+        ##........
+        ##........
+        ##........
+        ##........
+        ",
+    );
+    assert_eq!(game.lines_cleared, 0);
+}
+
+#[test]
+fn filling_a_row_clears_it() {
+    let mut game = game(
+        "
+        #.........
+        ####.#####
+        ",
+    );
+    place(&mut game, Shape::I, 1, 2);
+    assert_board(
+        &game,
+        "
+        ....#.....
+        ....#.....
+        #...#.....
+        ",
+    );
+    assert_eq!(game.lines_cleared, 1);
+    assert_eq!(game.score, 100);
+}
+
+#[test]
+fn four_rows_clear_at_once() {
+    let mut game = game(
+        "
+        #########.
+        #########.
+        #########.
+        #########.
+        ",
+    );
+    place(&mut game, Shape::I, 1, 7);
+    assert!(game.board.is_empty());
+    assert_eq!(game.lines_cleared, 4);
+    assert_eq!(game.score, 800);
+}
+
+#[test]
+fn failed_command_pushes_up_garbage() {
+    let mut game = game("#.........");
+    run_script(
+        &mut game,
+        "
+        START 1 cargo build
+        END 1 1
+        ",
+    )
+    .unwrap();
+    let bottom = game.board.height - 1;
+    let garbage = (0..BOARD_W)
+        .filter(|&x| matches!(game.board.get(x, bottom), Cell::Filled(..)))
+        .count();
+    assert_eq!(garbage, BOARD_W - 1, "a garbage row has exactly one hole");
+    assert!(matches!(game.board.get(0, bottom - 1), Cell::Filled(..)));
+    assert!(!game.game_over);
+}
+
+#[test]
+fn zen_forgives_failed_commands() {
+    let mut game = Game::with_seed(0);
+    game.start(Mode::Zen, Profile::Normal);
+    run_script(
+        &mut game,
+        "
+        START 1 cargo build
+        END 1 1
+        ",
+    )
+    .unwrap();
+    assert!(game.board.is_empty());
+}
+
+#[test]
+fn bomb_clears_around_itself() {
+    let mut game = game(
+        "
+        #########.
+        #########.
+        #########.
+        ",
+    );
+    place_bomb(&mut game, 4);
+    assert_board(
+        &game,
+        "
+        ###....##.
+        #########.
+        #########.
+        ",
+    );
+}
+
+#[test]
+fn bomb_that_fills_a_row_blasts_it_instead_of_clearing_it() {
+    let mut game = game(
+        "
+        ####..####
+        ####..####
+        ",
+    );
+    place_bomb(&mut game, 4);
+    assert!(game.pending_clear.is_empty());
+    assert_board(
+        &game,
+        "
+        ###....###
+        ###....###
+        ",
+    );
+}
+
+// Guideline points times the level, as most modern rulesets score.
+#[derive(Clone)]
+struct LevelScaled;
+
+impl ScoringRules for LevelScaled {
+    fn clear_points(&self, lines: u32, level: u64, combo: u32) -> u64 {
+        Guideline.clear_points(lines, level, combo) * level
+    }
+
+    fn variety_decay(&self, same_as_last: bool) -> i32 {
+        Guideline.variety_decay(same_as_last)
+    }
+
+    fn variety_points(&self, same_as_last: bool, streak: i32, failed: bool) -> i32 {
+        Guideline.variety_points(same_as_last, streak, failed)
+    }
+
+    fn bomb_threshold(&self) -> i32 {
+        Guideline.bomb_threshold()
+    }
+
+    fn bomb_cap(&self) -> i32 {
+        Guideline.bomb_cap()
+    }
+
+    fn boxed_clone(&self) -> Box<dyn ScoringRules> {
+        Box::new(self.clone())
+    }
+}
+
+#[test]
+fn scoring_rules_can_be_swapped() {
+    let mut game = game("#########.");
+    game.set_scoring_rules(LevelScaled);
+    game.lines_cleared = 20;
+    place(&mut game, Shape::I, 1, 7);
+    assert_eq!(game.score, 300);
+}
+
+#[test]
+fn board_reads_back_rows_and_columns() {
+    let mut board = board(
+        "
+        .#........
+        ##########
+        #.#.......
+        ",
+    );
+    assert!(board.is_row_full(BOARD_H - 2));
+    assert_eq!(board.full_rows(), [BOARD_H - 2]);
+    assert_eq!(board.column_height(1), 3);
+    assert_eq!(board.column_height(9), 2);
+    assert_eq!(board.iter_filled().count(), 13);
+
+    board.clear_row(BOARD_H - 2);
+    assert_eq!(board.row(BOARD_H - 2).iter().filter(|c| c.is_filled()).count(), 1);
+    let shown = board.to_string();
+    let bottom: Vec<&str> = shown.lines().rev().take(2).collect();
+    assert_eq!(bottom, ["#.#.......", ".#........"]);
+}
+
+#[test]
+fn sparks_draw_column_heights() {
+    let board = board(
+        "
+        .#........
+        ##########
+        #.#.......
+        ",
+    );
+    assert_eq!(board.sparks(), "▁▂▁▁▁▁▁▁▁▁");
+}
+
+#[test]
+fn commands_are_timed_by_the_game_clock() {
+    let mut game = game("");
+    let clock = ManualClock::default();
+    game.set_clock(clock.clone());
+    game.start_recording();
+    run_script(&mut game, "START 1 cargo test").unwrap();
+    clock.advance(Duration::from_secs(90));
+    assert_eq!(game.run_elapsed(1), Some(Duration::from_secs(90)));
+    run_script(&mut game, "END 1 0").unwrap();
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(game.run_elapsed(1), Some(Duration::from_secs(90)));
+    let longest = game.stats.longest_command.as_ref().map(|(_, took)| *took);
+    assert_eq!(longest, Some(Duration::from_secs(90)));
+    let last = game.recording().and_then(|r| r.steps.last()).map(|s| s.at_ms);
+    assert_eq!(last, Some(90_000));
+}
+
+#[test]
+fn restart_records_the_next_game_on_its_own() {
+    let mut game = Game::with_seed(3);
+    game.start_recording();
+    game.start(Mode::Classic, Profile::Normal);
+    run_script(&mut game, "START 1 cargo build\nDROP\nDROP").unwrap();
+    game.restart();
+    let steps = game.recording().map(|r| r.steps.len());
+    assert_eq!(steps, Some(1), "the last game's steps are gone");
+    run_script(&mut game, "DROP\nLEFT\nDROP\nEND 1 1\nDROP").unwrap();
+
+    let recording = game.recording().unwrap();
+    assert_eq!(recording.seed, game.seed);
+    let mut replayed = Game::with_seed(recording.seed);
+    for step in &recording.steps {
+        replayed.apply(step.step.clone());
+    }
+    assert_eq!(replayed.board.to_string(), game.board.to_string());
+    assert_eq!(replayed.score, game.score);
+    assert_eq!(replayed.current.shape, game.current.shape);
+}
+
+#[test]
+fn board_diff_carries_one_board_to_another() {
+    let before = board(
+        "
+        ..........
+        #########.
+        ",
+    );
+    let mut after = before.clone();
+    after.set(9, BOARD_H - 1, Cell::Filled('x', 'y', Origin::Filler));
+    after.clear_row(BOARD_H - 1);
+    let diff = before.diff(&after);
+    assert_eq!(diff.changes.len(), 9);
+    assert_eq!(diff.dirty_rows(), [BOARD_H - 1]);
+
+    let mut copy = before.clone();
+    diff.apply(&mut copy);
+    assert_eq!(copy.cells, after.cells);
+    assert!(after.diff(&copy).is_empty());
+
+    let mut small = Board::new(4, 4);
+    diff.apply(&mut small);
+    assert_eq!(small.cells.len(), BOARD_W * BOARD_H);
+}
+
+#[test]
+fn reported_output_falls_as_result_pieces() {
+    let mut game = game("");
+    run_script(
+        &mut game,
+        "
+        START 1 ls
+        OUTPUT 1 ls: cannot access 'x'
+        END 1 2
+        DROP
+        ",
+    )
+    .unwrap();
+    assert_eq!(game.output_for_run(1), Some("ls: cannot access 'x'"));
+    assert_eq!(game.current.shape, Shape::I);
+    assert!(matches!(game.current.origin, Origin::Result { run: 1 }));
+    assert_eq!(game.current.payload.iter().collect::<String>(), "ls: cann");
+    assert_eq!(game.pieces_for_run(1), 4, "the command's own piece and three result bars");
+}
+
+#[test]
+fn running_commands_deal_repeats_by_play_time() {
+    let mut game = game("");
+    run_script(&mut game, "START 1 make\nDROP").unwrap();
+    assert!(!game.active_piece, "the first pass is one piece");
+    game.step(None, Duration::from_secs(REPEAT_SECS) / 2);
+    assert!(!game.active_piece);
+    game.step(None, Duration::from_secs(REPEAT_SECS) / 2);
+    assert!(game.active_piece, "one repeat a pace");
+    assert_eq!(game.pieces_for_run(1), 2);
+    run_script(&mut game, "DROP\nEND 1 0").unwrap();
+    game.step(None, Duration::from_secs(REPEAT_SECS * 2));
+    assert!(!game.active_piece, "nothing more once it ends");
+}
+
+#[test]
+fn resumed_game_keeps_its_play_time() {
+    let mut saved = game("");
+    saved.step(None, Duration::from_secs(60));
+    let saved: Game = serde_json::from_str(&serde_json::to_string(&saved).unwrap()).unwrap();
+    let mut game = game("");
+    run_script(&mut game, "START 1 make").unwrap();
+    game.resume(saved);
+    assert_eq!(game.play_time(), Duration::from_secs(60));
+    // The run carried in is paced from there, not due a repeat straight away.
+    while game.active_piece {
+        run_script(&mut game, "DROP").unwrap();
+    }
+    game.step(None, Duration::from_secs(REPEAT_SECS) / 2);
+    assert!(!game.active_piece);
+    game.step(None, Duration::from_secs(REPEAT_SECS) / 2);
+    assert!(game.active_piece);
+}
+
+#[test]
+fn trickle_feeds_heartbeats_instead_of_repeats() {
+    let mut game = game("");
+    game.set_feed(Feed::Trickle { every: Duration::from_secs(10) });
+    run_script(&mut game, "START 1 make\nDROP").unwrap();
+    game.step(None, Duration::from_secs(REPEAT_SECS));
+    assert!(!game.active_piece, "no repeats");
+    game.step(None, Duration::from_secs(10 - REPEAT_SECS));
+    assert!(game.active_piece);
+    assert!(matches!(game.current.origin, Origin::Filler));
+    assert!(game.current.payload.iter().all(|&c| c == '░'));
+}
+
+#[test]
+fn interrupted_commands_cancel_without_garbage() {
+    let mut game = game("#.........");
+    run_script(&mut game, "START 1 cargo build --release\nEND 1 130").unwrap();
+    assert_eq!(game.pieces_for_run(1), 1, "only the falling piece is left");
+    assert_board(&game, "#.........");
+}
+
+#[test]
+fn crashes_push_up_more_garbage_than_failures() {
+    let mut failed = game("");
+    run_script(&mut failed, "START 1 ./a.out\nEND 1 1").unwrap();
+    let mut crashed = game("");
+    run_script(&mut crashed, "START 1 ./a.out\nEND 1 139").unwrap();
+    let rows = |game: &Game| {
+        (0..BOARD_H).filter(|&y| game.board.row(y).iter().any(|c| c.is_filled())).count()
+    };
+    assert_eq!(rows(&failed), 1);
+    assert_eq!(rows(&crashed), CRASH_GARBAGE);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "tui")]
+
+use stack_game::app::Boards;
+use stack_game::game::{Mode, Profile};
+use stack_game::io::parse_command_line;
+use stack_game::testing::settle;
+use stack_game::Game;
+
+fn started() -> Game {
+    let mut game = Game::with_seed(1);
+    game.start(Mode::Classic, Profile::Normal);
+    game
+}
+
+// One socket line, routed the way the app loop routes it.
+fn send(boards: &mut Boards, game: &mut Game, line: &str) {
+    let ev = parse_command_line(line).expect("a protocol line");
+    boards.board_for(game, &ev).handle_command_event(ev);
+}
+
+// Drop everything the board on screen still has coming.
+fn land_all(game: &mut Game) {
+    for _ in 0..50 {
+        if !game.is_running() {
+            break;
+        }
+        game.hard_drop();
+        settle(game);
+    }
+    assert!(!game.is_running());
+}
+
+#[test]
+fn one_board_takes_every_command() {
+    let mut boards = Boards::new(false);
+    let mut game = started();
+    send(&mut boards, &mut game, "START 1 make");
+    send(&mut boards, &mut game, "START 2 cargo test");
+    assert_eq!(game.active_run_count(), 2);
+    assert!(boards.tidy(&mut game).is_empty());
+}
+
+#[test]
+fn a_second_command_gets_a_board_of_its_own() {
+    let mut boards = Boards::new(true);
+    let mut game = started();
+    send(&mut boards, &mut game, "START 1 make");
+    send(&mut boards, &mut game, "START 2 cargo test");
+    assert_eq!(game.command_for_run(2), None, "the main game keeps the screen");
+
+    boards.switch(&mut game);
+    assert_eq!(game.command_for_run(2), Some("cargo test"));
+    assert_eq!(boards.main(&game).command_for_run(1), Some("make"));
+    // Events follow their run to whichever board it's on.
+    send(&mut boards, &mut game, "END 1 0");
+    assert_eq!(boards.main(&game).active_run_count(), 0);
+    assert_eq!(game.active_run_count(), 1);
+
+    boards.show_main(&mut game);
+    assert_eq!(game.command_for_run(1), Some("make"));
+    boards.switch(&mut game);
+    assert_eq!(game.command_for_run(2), Some("cargo test"), "Tab comes back round");
+}
+
+#[test]
+fn a_start_with_a_side_board_on_screen_goes_to_the_main_game() {
+    let mut boards = Boards::new(true);
+    let mut game = started();
+    send(&mut boards, &mut game, "START 1 make");
+    send(&mut boards, &mut game, "START 2 cargo test");
+    boards.switch(&mut game);
+    send(&mut boards, &mut game, "END 1 0");
+    send(&mut boards, &mut game, "END 2 0");
+    assert!(game.is_running(), "the side board still has a piece falling");
+
+    send(&mut boards, &mut game, "START 3 ls");
+    assert_eq!(game.command_for_run(3), None);
+    assert_eq!(boards.main(&game).command_for_run(3), Some("ls"));
+}
+
+#[test]
+fn finished_side_boards_retire() {
+    let mut boards = Boards::new(true);
+    let mut game = started();
+    send(&mut boards, &mut game, "START 1 make");
+    send(&mut boards, &mut game, "START 2 cargo test");
+    send(&mut boards, &mut game, "END 2 0");
+    assert!(boards.tidy(&mut game).is_empty(), "its pieces haven't landed");
+
+    boards.switch(&mut game);
+    land_all(&mut game);
+    let retired = boards.tidy(&mut game);
+    assert_eq!(retired.len(), 1);
+    assert_eq!(game.command_for_run(1), Some("make"), "the main game is back on screen");
+    assert!(boards.tidy(&mut game).is_empty());
+}
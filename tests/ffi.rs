@@ -0,0 +1,26 @@
+#![cfg(feature = "ffi")]
+
+use std::ffi::CString;
+
+use stack_game::ffi::*;
+use stack_game::{BOARD_H, BOARD_W};
+
+#[test]
+fn c_api_plays_a_command() {
+    let mut game = waitris_game_new(0, 0);
+    let start = CString::new("START 1 cargo build").unwrap();
+    assert_eq!(unsafe { waitris_game_event(Some(&mut game), start.as_ptr()) }, 0);
+    let junk = CString::new("HELLO").unwrap();
+    assert_eq!(unsafe { waitris_game_event(Some(&mut game), junk.as_ptr()) }, -1);
+
+    let mut buf = vec![0u8; BOARD_W * BOARD_H];
+    let cells = unsafe { waitris_game_board(Some(&game), buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(cells, buf.len());
+    assert_eq!(buf.iter().filter(|&&c| c == 2).count(), 4);
+
+    waitris_game_step(Some(&mut game), 4, 1000);
+    unsafe { waitris_game_board(Some(&game), buf.as_mut_ptr(), buf.len()) };
+    assert!(buf[(BOARD_H - 1) * BOARD_W..].contains(&1));
+    assert!(!waitris_game_over(Some(&game)));
+    waitris_game_free(Some(game));
+}
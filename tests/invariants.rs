@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use stack_game::game::{Action, Mode, Profile};
+use stack_game::{CommandEvent, Game};
+
+const COMMANDS: [&str; 5] = ["cargo build", "ls", "git push --force-with-lease", "make -j8 test", ""];
+const ACTIONS: [Action; 5] = [Action::Left, Action::Right, Action::SoftDrop, Action::Rotate, Action::HardDrop];
+
+// Drive a game with a random mix of commands, keys and frames, checking the invariants after
+// each one. Debug builds also check them inside the game; this catches release-only gaps.
+fn play_random(seed: u64, steps: usize) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut game = Game::with_seed(seed);
+    game.start(*Mode::ALL.choose(&mut rng).unwrap(), Profile::Normal);
+    let mut running = Vec::new();
+    let mut next_id = 1;
+    for _ in 0..steps {
+        match rng.gen_range(0..40) {
+            0..=2 => {
+                let command = COMMANDS.choose(&mut rng).unwrap().to_string();
+                game.handle_command_event(CommandEvent::Start { id: next_id, command });
+                running.push(next_id);
+                next_id += 1;
+            }
+            3..=5 if !running.is_empty() => {
+                let id = running.swap_remove(rng.gen_range(0..running.len()));
+                let exit_code = *[0, 0, 1, 130].choose(&mut rng).unwrap();
+                game.handle_command_event(CommandEvent::End { id, _exit_code: exit_code });
+            }
+            6 => game.restart(),
+            7..=24 => game.step(ACTIONS.choose(&mut rng).copied(), Duration::ZERO),
+            _ => game.step(None, Duration::from_millis(rng.gen_range(0..800))),
+        }
+        game.assert_invariants();
+    }
+}
+
+#[test]
+fn random_play_keeps_invariants() {
+    for seed in 0..200 {
+        play_random(seed, 400);
+    }
+}
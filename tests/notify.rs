@@ -0,0 +1,20 @@
+#![cfg(feature = "tui")]
+
+use stack_game::io::Osc;
+
+#[test]
+fn osc_notifications_keep_to_one_escape() {
+    assert_eq!(
+        Osc::Nine.notification("Command failed", "make (exit 2)"),
+        "\x1b]9;Command failed: make (exit 2)\x07"
+    );
+    assert_eq!(
+        Osc::SevenSevenSeven.notification("a;b", "echo hi; ls"),
+        "\x1b]777;notify;a b;echo hi; ls\x07"
+    );
+    // A command carrying its own escapes can't end the notification early.
+    assert_eq!(
+        Osc::Nine.notification("t", "printf '\x07\x1b]0;x'"),
+        "\x1b]9;t: printf '  ]0;x'\x07"
+    );
+}
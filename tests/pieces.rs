@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use stack_game::game::{
+    evaluate, Action, Autoplayer, Board, KickTable, Mode, Piece, Profile, Scripted, SevenBag, Shape,
+};
+use stack_game::testing::settle;
+use stack_game::{CommandEvent, Game, BOARD_H, BOARD_W, CHUNK_SIZE};
+
+#[test]
+fn scripted_source_deals_in_order() {
+    let mut game = Game::with_seed(0);
+    game.set_piece_source(Scripted::new(vec![Shape::T, Shape::O]));
+    game.start(Mode::Classic, Profile::Normal);
+    game.handle_command_event(CommandEvent::Start {
+        id: 1,
+        command: "cargo build --release".into(),
+    });
+    let mut dealt = vec![game.current.shape];
+    for _ in 0..3 {
+        game.hard_drop();
+        settle(&mut game);
+        dealt.push(game.current.shape);
+    }
+    assert_eq!(dealt, [Shape::T, Shape::O, Shape::T, Shape::O]);
+}
+
+#[test]
+fn scripted_source_starts_over_on_restart() {
+    let mut game = Game::with_seed(0);
+    game.set_piece_source(Scripted::new(vec![Shape::T, Shape::O, Shape::I]));
+    game.start(Mode::Classic, Profile::Normal);
+    game.handle_command_event(CommandEvent::Start {
+        id: 1,
+        command: "cargo build --release".into(),
+    });
+    game.hard_drop();
+    settle(&mut game);
+    assert_eq!(game.current.shape, Shape::O);
+
+    game.restart();
+    game.handle_command_event(CommandEvent::Start {
+        id: 2,
+        command: "cargo test --workspace".into(),
+    });
+    let mut dealt = vec![game.current.shape];
+    for _ in 0..2 {
+        game.hard_drop();
+        settle(&mut game);
+        dealt.push(game.current.shape);
+    }
+    assert_eq!(dealt, [Shape::T, Shape::O, Shape::I]);
+}
+
+#[test]
+fn seven_bag_deals_every_shape_once_per_bag() {
+    let mut game = Game::with_seed(0);
+    game.set_piece_source(SevenBag::default());
+    game.start(Mode::Sprint, Profile::Normal);
+    let mut dealt = Vec::new();
+    for _ in 0..7 {
+        dealt.push(game.current.shape);
+        game.hard_drop();
+        settle(&mut game);
+        game.board = Board::new(BOARD_W, BOARD_H);
+    }
+    dealt.sort_by_key(|&shape| shape as u8);
+    assert_eq!(dealt, [Shape::I, Shape::O, Shape::T, Shape::S, Shape::Z, Shape::J, Shape::L]);
+}
+
+// The first few shapes a game deals with nothing running, filler only.
+fn opening(game: &mut Game) -> Vec<Shape> {
+    let mut dealt = Vec::new();
+    for _ in 0..5 {
+        dealt.push(game.current.shape);
+        game.hard_drop();
+        settle(game);
+        game.board = Board::new(BOARD_W, BOARD_H);
+    }
+    dealt
+}
+
+#[test]
+fn restarted_game_replays_from_its_own_seed() {
+    let mut game = Game::with_seed(7);
+    game.set_piece_source(SevenBag::default());
+    game.start(Mode::Sprint, Profile::Normal);
+    opening(&mut game);
+    game.restart();
+    assert_ne!(game.seed, 7);
+
+    let mut again = Game::with_seed(game.seed);
+    again.set_piece_source(SevenBag::default());
+    again.start(Mode::Sprint, Profile::Normal);
+    assert_eq!(opening(&mut game), opening(&mut again));
+}
+
+// A vertical bar against the left wall, turned flat: blocked outright unless the rules kick.
+fn turn_against_wall(game: &mut Game) -> Option<i32> {
+    let mut bar = Piece::with_payload(Shape::I, vec!['#'; CHUNK_SIZE]);
+    bar.rotation = 1;
+    bar.x = -2;
+    bar.y = 5;
+    game.current = bar;
+    game.active_piece = true;
+    game.rotate_current().then(|| game.current.cells().iter().map(|c| c.0).min().unwrap_or(0))
+}
+
+#[test]
+fn sprint_kicks_off_the_wall() {
+    let mut game = Game::with_seed(0);
+    game.start(Mode::Sprint, Profile::Normal);
+    assert_eq!(turn_against_wall(&mut game), Some(0));
+}
+
+#[test]
+fn classic_rotation_does_not_kick() {
+    let mut game = Game::with_seed(0);
+    game.start(Mode::Classic, Profile::Normal);
+    assert_eq!(turn_against_wall(&mut game), None);
+
+    let mut table = KickTable::default();
+    table.set(Shape::I, 1, vec![(3, 0)]);
+    game.set_kick_table(Some(table));
+    assert_eq!(turn_against_wall(&mut game), Some(1));
+}
+
+#[test]
+fn legal_placements_include_tucks_under_overhangs() {
+    let mut game = stack_game::testing::game(
+        "
+        ###.......
+        #.........
+        ",
+    );
+    game.current = Piece::with_payload(Shape::I, vec!['#'; CHUNK_SIZE]);
+    game.active_piece = true;
+    let placements = game.legal_placements();
+    // Flat: three on the ledge, four on the floor and two slid under the ledge; upright: ten.
+    assert_eq!(placements.len(), 19);
+
+    let floor = BOARD_H as i32 - 1;
+    let tucked: Vec<(i32, i32)> = (1..5).map(|x| (x, floor)).collect();
+    let tuck = placements
+        .iter()
+        .find(|p| p.piece.cells().iter().map(|&(x, y, _)| (x, y)).eq(tucked.iter().copied()))
+        .expect("the slide under the ledge");
+    assert!(tuck.actions.ends_with(&[Action::Left, Action::Left, Action::HardDrop]));
+    assert!(evaluate(&tuck.landed(&game.board)) > evaluate(&placements[0].landed(&game.board)));
+
+    for action in tuck.actions.clone() {
+        game.input(action);
+    }
+    stack_game::testing::assert_board(
+        &game,
+        "
+        ###.......
+        #####.....
+        ",
+    );
+}
+
+#[test]
+fn autoplayer_finishes_a_sprint() {
+    let mut game = Game::with_seed(0);
+    game.start(Mode::Sprint, Profile::Normal);
+    let mut bot = Autoplayer::new(Duration::from_millis(50));
+    let frame = Duration::from_millis(25);
+    while !game.game_over {
+        let action = bot.next_action(&game, frame);
+        game.step(action, frame);
+    }
+    assert!(game.goal_reached(), "topped out after {} lines\n{}", game.lines_cleared, game.board);
+}
@@ -0,0 +1,49 @@
+use stack_game::io::parse_command_line;
+use stack_game::CommandEvent;
+
+fn start(id: u64, command: &str) -> Option<CommandEvent> {
+    Some(CommandEvent::Start { id, command: command.into() })
+}
+
+fn output(id: u64, line: &str) -> Option<CommandEvent> {
+    Some(CommandEvent::Output { id, line: line.into() })
+}
+
+fn end(id: u64, exit_code: i32) -> Option<CommandEvent> {
+    Some(CommandEvent::End { id, _exit_code: exit_code })
+}
+
+#[test]
+fn reads_hook_lines() {
+    assert_eq!(parse_command_line("START 1 cargo build\n"), start(1, "cargo build"));
+    assert_eq!(parse_command_line("  START 2   ls -la  "), start(2, "ls -la"));
+    assert_eq!(parse_command_line("START 3"), start(3, ""));
+    assert_eq!(parse_command_line("END 1 0"), end(1, 0));
+    assert_eq!(parse_command_line("END 1 130 killed"), end(1, 130));
+    assert_eq!(parse_command_line("END 1"), end(1, 0));
+    assert_eq!(parse_command_line("END 1 oops"), end(1, 0));
+    assert_eq!(parse_command_line("OUTPUT 4 linker failed "), output(4, "linker failed"));
+    assert_eq!(parse_command_line("OUTPUT 4"), output(4, ""));
+}
+
+#[test]
+fn rejects_everything_else() {
+    let lines = [
+        "", "QUERY", "start 1 ls", "START", "START x ls", "START -1 ls", "START\t1 ls", "END", "END x 0",
+        "OUTPUT", "OUTPUT x oops",
+    ];
+    for line in lines {
+        assert_eq!(parse_command_line(line), None, "{line:?}");
+    }
+}
+
+#[test]
+fn survives_the_fuzz_corpus() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/corpus/parse_command_line");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let bytes = std::fs::read(entry.unwrap().path()).unwrap();
+        if let Ok(line) = std::str::from_utf8(&bytes) {
+            parse_command_line(line);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "tui")]
+
+use stack_game::game::Shape;
+use stack_game::testing::{game, place, render_to_string};
+
+// The size `stack-game --simulate --render` draws at: room for the INFO and CONTROLS boxes.
+const WIDTH: u16 = 50;
+const HEIGHT: u16 = 40;
+
+const FRAME: &str = "
+┏WAITRIS━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+┃                                                ┃
+┌INFO───────────────────────────────────────────┐┃
+│SCORE:  0                 BOMBS: 0             │┃
+│LINES:  0                       VAR 0/100      │┃
+│STATUS: IDLE              HI:    0             │┃
+│BEST:   0                 LV 1 450ms           │┃
+│TIME:   0s                WAIT:  0s            │┃
+└─────────────────────────────────────────seed 0┘┃
+┃   ┌────────────────────────────────────────┐   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │                                        │   ┃
+┃   │    ▓▓▓▓▓▓▓▓                            │   ┃
+┃   │    ▓▓▓▓▓▓▓▓                            │   ┃
+┃   │#░░░#░░░#░░░#░░░    #░░░#░░░#░░░#░░░#░░░│   ┃
+┃   └════════════════════════════════════════┘   ┃
+┃                                                ┃
+┌CONTROLS───────────────────────────────────────┐┃
+│←/→ move                  ↑ rotate             │┃
+│↓ soft                    space slam           │┃
+│p pause                   ? help               │┃
+│q quit                    Ctrl+Space swap      │┃
+└───────────────────────────────────────────────┘┃
+┃                                                ┃
+┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛";
+
+#[test]
+fn frame_shows_the_board_and_the_hud() {
+    let mut game = game(
+        "
+        ####.#####
+        ",
+    );
+    place(&mut game, Shape::O, 0, 0);
+    assert_eq!(render_to_string(&game, WIDTH, HEIGHT, false), FRAME.trim_start_matches('\n'));
+}
+
+#[test]
+fn pause_menu_covers_the_well_and_leaves_the_hud() {
+    let game = game("");
+    let frame = render_to_string(&game, WIDTH, HEIGHT, true);
+    let rows: Vec<&str> = frame.lines().collect();
+    assert_eq!(rows.len(), HEIGHT as usize);
+    assert!(rows.iter().any(|row| row.contains("╔═══════PAUSED═══════╗")), "{frame}");
+    assert!(rows.iter().any(|row| row.contains("> RESUME <")), "{frame}");
+    assert!(rows[3].starts_with("│SCORE:  0"), "{frame}");
+}
@@ -0,0 +1,38 @@
+use std::fs;
+
+use stack_game::game::Mode;
+use stack_game::persist::{
+    export_stats, import_stats, load_high_scores, save_high_scores, ScoreEntry,
+};
+
+// The only test here: it points the data dir at a scratch directory for the whole process.
+#[test]
+fn imported_high_scores_are_never_signed_here() {
+    let dir = std::env::temp_dir().join(format!("waitris-scores-{}", std::process::id()));
+    // SAFETY: no other thread in this test binary reads the environment.
+    unsafe { std::env::set_var("XDG_DATA_HOME", &dir) };
+    save_high_scores(&[ScoreEntry::new(100, 4, Mode::Classic, "ours".into())]).unwrap();
+
+    // Another install's export, with its score edited by hand.
+    let export = dir.join("export.json");
+    let export = export.to_str().unwrap();
+    export_stats(export).unwrap();
+    let forged = fs::read_to_string(export).unwrap().replace("\"score\": 100", "\"score\": 999999");
+    fs::write(export, forged).unwrap();
+    let _ = import_stats(export);
+
+    let scores = load_high_scores();
+    let find = |scores: &[ScoreEntry], score| scores.iter().find(|e| e.score == score).cloned();
+    let theirs = find(&scores, 999_999).unwrap();
+    assert!(theirs.imported && !theirs.flagged);
+    let ours = find(&scores, 100).unwrap();
+    assert!(!ours.imported && !ours.flagged);
+
+    // Passing it off as our own breaks the signature it came with.
+    let table = dir.join("waitris").join("high_scores.json");
+    let text = fs::read_to_string(&table).unwrap();
+    fs::write(&table, text.replace("\"imported\": true", "\"imported\": false")).unwrap();
+    let theirs = find(&load_high_scores(), 999_999).unwrap();
+    assert!(theirs.flagged);
+    let _ = fs::remove_dir_all(&dir);
+}